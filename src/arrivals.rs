@@ -0,0 +1,105 @@
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::entity::Menu;
+
+const DAY: &str = include_str!("../assets/day.toml");
+const REGULAR: &str = include_str!("../assets/queues/regular.toml");
+const VOLUNTEERS: &str = include_str!("../assets/queues/volunteers.toml");
+
+/// When the polls are open
+#[derive(Deserialize)]
+pub struct Day {
+    /// Hour the polls open
+    pub open: f64,
+    /// Hour the polls close
+    pub close: f64,
+    /// Seconds of play for the whole day
+    pub day_length: f64,
+}
+
+impl Day {
+    pub fn load() -> Day {
+        toml::from_str(DAY).expect("Invalid polling day")
+    }
+}
+
+/// Who comes to one of the queues, when, and what they can order
+#[derive(Deserialize)]
+pub struct Arrivals {
+    rate: Vec<Rate>,
+    /// Chance each customer is ordering for a group
+    #[serde(default)]
+    groups: f64,
+    /// Most people a group orders for
+    #[serde(default = "default_group")]
+    max_group: usize,
+    pub menu: Menu,
+}
+
+fn default_group() -> usize {
+    1
+}
+
+#[derive(Deserialize)]
+struct Rate {
+    hour: f64,
+    customers_per_second: f64,
+}
+
+impl Arrivals {
+    /// The main queue of voters
+    pub fn regular() -> Arrivals {
+        Arrivals::load(REGULAR)
+    }
+
+    /// Polling officials and volunteers on their breaks
+    pub fn volunteers() -> Arrivals {
+        Arrivals::load(VOLUNTEERS)
+    }
+
+    fn load(data: &str) -> Arrivals {
+        let arrivals: Arrivals = toml::from_str(data).expect("Invalid arrival profile");
+        assert!(arrivals.menu.can_order(), "Nothing to order on the arrival profile's menu");
+        arrivals
+    }
+
+    /// How many breads the next customer orders
+    pub fn group_size(&self) -> usize {
+        let mut rng = rand::thread_rng();
+        if self.max_group > 1 && rng.gen::<f64>() < self.groups {
+            rng.gen_range(2, self.max_group + 1)
+        } else {
+            1
+        }
+    }
+
+    /// Customers per second at `hour`, interpolated between the listed times
+    pub fn rate(&self, hour: f64) -> f64 {
+        let lower = self.rate.iter().rfind(|r| r.hour <= hour);
+        let upper = self.rate.iter().find(|r| r.hour > hour);
+        match (lower, upper) {
+            (None, None) => 0.0,
+            (Some(r), None) | (None, Some(r)) => r.customers_per_second,
+            (Some(l), Some(u)) => {
+                let s = (hour - l.hour) / (u.hour - l.hour);
+                l.customers_per_second * (1.0 - s) + u.customers_per_second * s
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_queue_has_something_to_order() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/queues");
+        for file in std::fs::read_dir(dir).unwrap() {
+            let path = file.unwrap().path();
+            let arrivals: Arrivals = toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+            assert!(arrivals.menu.can_order(), "Nothing to order in {}", path.display());
+        }
+    }
+}
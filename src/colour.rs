@@ -1,8 +1,43 @@
+use serde::{Deserialize, Serialize};
+
 type Colour = [f32; 4];
 
+const STANDARD_MOODS: [Colour; 3] = [[0.3, 1.0, 0.4, 1.0], [0.9, 0.9, 0.1, 1.0], [1.0, 0.1, 0.1, 1.0]];
+// Blue, yellow and vermilion stay apart with the common kinds of colour blindness
+const COLOURBLIND_MOODS: [Colour; 3] = [[0.34, 0.71, 0.91, 1.0], [0.94, 0.89, 0.26, 1.0], [0.84, 0.37, 0.0, 1.0]];
+
+/// The colours used to show how customers feel
+#[derive(Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Palette {
+    #[default]
+    Standard,
+    Colourblind,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 2] = [Palette::Standard, Palette::Colourblind];
+
+    /// Message id of the palette's name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Palette::Standard => "palette_standard",
+            Palette::Colourblind => "palette_colourblind",
+        }
+    }
+
+    /// Colours for happy, neutral and sad
+    pub fn moods(&self) -> [Colour; 3] {
+        match self {
+            Palette::Standard => STANDARD_MOODS,
+            Palette::Colourblind => COLOURBLIND_MOODS,
+        }
+    }
+}
+
 pub fn interpolate_colour(colours: &[(Colour, f32)], point: f32) -> Colour {
-    let lower = colours.iter().filter(|(_, x)| *x < point).last();
-    let upper = colours.iter().filter(|(_, x)| *x >= point).next();
+    let lower = colours.iter().rfind(|(_, x)| *x < point);
+    let upper = colours.iter().find(|(_, x)| *x >= point);
 
     match (lower, upper) {
         (None, None) => panic!("Gradient missing reference points"),
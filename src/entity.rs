@@ -1,13 +1,18 @@
+use std::any::Any;
 use std::rc::Rc;
 use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
 
-use crate::geometry::Rectangle;
-use crate::colour::interpolate_colour;
+use crate::geometry::{Rectangle, OrientedRectangle, Shape};
+use crate::colour::{interpolate_colour, Palette};
+use crate::arrivals::{Arrivals, Day};
+use crate::text::{Align, Font};
 
-use piston_window::{context::Context,G2d};
+use piston_window::{context::Context,G2d,Transformed};
 use noise::{Seedable, NoiseFn};
-use rand::{Rng,distributions::Bernoulli};
-use rand_distr::Beta;
+use rand::{Rng,distributions::{Bernoulli,WeightedIndex}};
+use serde::Deserialize;
 
 pub type G<'a> = G2d<'a>;
 
@@ -17,15 +22,42 @@ pub enum Selection {
     New(Rc<RefCell<dyn Entity>>),
 }
 
-pub trait Entity {
+/// Something held that works on whatever is beneath it
+#[derive(Clone, Copy, PartialEq)]
+pub enum Tool {
+    Spatula,
+    Bottle(Condiment),
+}
+
+/// Lets an entity be looked at as its concrete type, for merging it into another of the same kind
+pub trait AsAny {
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+pub trait Entity: AsAny {
     fn bounds(&self) -> Rectangle;
+    fn shape(&self) -> Shape { Shape::Rectangle(self.bounds()) }
+    fn footprint(&self) -> OrientedRectangle { self.bounds().into() }
+    fn solid(&self) -> bool { false }
+    fn placements(&self, _pos: [f64; 2]) -> Option<Vec<[f64; 2]>> { None }
     fn select(&mut self, _pos: [f64; 2]) -> Selection { Selection::None }
     fn update(&mut self, _dt: f64) -> Vec<Rc<RefCell<dyn Entity>>> { vec![] }
     fn update_selected(&mut self, _dt: f64) {}
     fn grab(&mut self) {}
     fn drop(&mut self) {}
     fn drag(&mut self, _from: [f64; 2], _to: [f64; 2]) {}
+    fn rotate(&mut self, _angle: f64) {}
+    fn squeeze(&mut self, _squeezing: bool) {}
+    fn take_tool_use(&mut self) -> Option<(Tool, Vec<[f64; 2]>)> { None }
+    fn use_tool(&mut self, _tool: Tool, _pos: [f64; 2]) -> bool { false }
     fn draw(&self, context: Context, graphics: &mut G);
+    fn draw_text(&self, _context: Context, _font: &mut Font, _graphics: &mut G) {}
     fn set_pos(&mut self, _pos: [f64; 2]) {}
     fn get_pos(& self) -> [f64; 2] {self.bounds().centre()}
     fn topping(&self) -> Option<Topping> { None }
@@ -34,8 +66,13 @@ pub trait Entity {
     fn set_heat(&mut self, _heat: f64) {}
     fn heat(&self, _pos: [f64; 2]) -> f64 { 0.0 }
     fn cooked(&self) -> [f64; 2] { [0.0, 0.0] }
+    fn freshness(&self) -> f64 { 1.0 }
+    fn portion(&self) -> f64 { 1.0 }
+    fn ready(&self) -> bool { false }
+    fn warning(&self) -> Option<&'static str> { None }
     fn expired(&self) -> bool { false }
     fn order(&self) -> Option<&Bread> { None }
+    fn order_overlap(&self, _order: &Bread) -> f64 { 0.0 }
     fn deliver_order(&mut self, _order: &Bread) -> Option<Mood> { None }
     fn other_dropped(&mut self, _other: &Rc<RefCell<dyn Entity>>) {}
 }
@@ -44,13 +81,18 @@ const SAUSAGE_SIZE: [f64; 2] = [13.0, 65.0];
 const SAUSAGE_TRAY: [f64; 2] = [105.0, 77.0];
 const PATTY_SIZE: [f64; 2] = [50.0, 36.0];
 const PATTY_TRAY: [f64; 2] = [62.0, 87.0];
+const BACON_SIZE: [f64; 2] = [16.0, 60.0];
+const BACON_TRAY: [f64; 2] = [84.0, 70.0];
+const EGG_SIZE: [f64; 2] = [46.0, 40.0];
+const EGG_TRAY: [f64; 2] = [66.0, 52.0];
+const HALLOUMI_SIZE: [f64; 2] = [44.0, 28.0];
+const HALLOUMI_TRAY: [f64; 2] = [54.0, 68.0];
 const PLATE_SIZE: [f64; 2] = [78.0, 78.0];
 const SAUSAGE_OFFSET: f64 = 10.0;
 const BREAD_SIZE: [f64; 2] = [53.0, 53.0];
 const LOAF_SIZE: [f64; 2] = [53.0, 120.0];
+const TUB_SIZE: [f64; 2] = [22.0, 20.0];
 const WHITE: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
-const HAPPY: [f32; 4] = [0.3, 1.0, 0.4, 1.0];
-const NEUTRAL: [f32; 4] = [0.9, 0.9, 0.1, 1.0];
 const SAD: [f32; 4] = [1.0, 0.1, 0.1, 1.0];
 const LIGHT_GREY: [f32; 4] = [0.95, 0.95, 0.95, 1.0];
 const DARK_GREY: [f32; 4] = [0.35, 0.35, 0.4, 1.0];
@@ -60,14 +102,64 @@ const ORANGE: [f32; 4] = [247.0 / 255.0, 155.0 / 255.0, 27.0 / 255.0, 1.0];
 const BROWN: [f32; 4] = [204.0 / 255.0, 103.0 / 255.0, 26.0 / 255.0, 1.0];
 const BLACK: [f32; 4] = [79.0 / 255.0, 48.0 / 255.0, 24.0 / 255.0, 1.0];
 const GREEN: [f32; 4] = [53.0 / 255.0, 201.0 / 255.0, 12.0 / 255.0, 1.0];
+const BACON: [f32; 4] = [232.0 / 255.0, 112.0 / 255.0, 112.0 / 255.0, 1.0];
+const CRISPY_BACON: [f32; 4] = [166.0 / 255.0, 58.0 / 255.0, 38.0 / 255.0, 1.0];
+const FAT: [f32; 4] = [250.0 / 255.0, 232.0 / 255.0, 222.0 / 255.0, 1.0];
+const GOLDEN: [f32; 4] = [232.0 / 255.0, 180.0 / 255.0, 86.0 / 255.0, 1.0];
+const RAW_EGG: [f32; 4] = [1.0, 1.0, 1.0, 0.5];
+const YOLK: [f32; 4] = [1.0, 170.0 / 255.0, 0.0, 1.0];
+const SET_YOLK: [f32; 4] = [1.0, 210.0 / 255.0, 90.0 / 255.0, 1.0];
+const SHELL: [f32; 4] = [222.0 / 255.0, 178.0 / 255.0, 130.0 / 255.0, 1.0];
+const CARTON: [f32; 4] = [180.0 / 255.0, 170.0 / 255.0, 150.0 / 255.0, 1.0];
+const HALLOUMI: [f32; 4] = [250.0 / 255.0, 245.0 / 255.0, 225.0 / 255.0, 1.0];
+const STALE: [f32; 4] = [0.6, 0.6, 0.55, 1.0];
+const ELEMENT: [f32; 4] = [1.0, 0.45, 0.1, 0.6];
+const CRUST: [f32; 4] = [194.0 / 255.0, 153.0 / 255.0, 26.0 / 255.0, 1.0];
+const CRUMB: [f32; 4] = [1.0, 246.0 / 255.0, 206.0 / 255.0, 1.0];
+const TOAST: [f32; 4] = [222.0 / 255.0, 170.0 / 255.0, 80.0 / 255.0, 1.0];
 const BOARD: [f32; 4] = [156.0 / 244.0, 244.0 / 241.0, 243.0 / 255.0, 1.0];
+const PICK_TOLERANCE: f64 = 3.0;
+const GRID_CELL: f64 = 20.0;
+const GRID_SEARCH: i32 = 3;
+const GRID_LINE: [f32; 4] = [1.0, 1.0, 1.0, 0.08];
+const WARMING_HEAT: f64 = 0.0015;
+const WARM_UP_SPEED: f64 = 0.5;
+const COOL_TIME: f64 = 40.0;
+const STALE_TIME: f64 = 90.0;
+const HELD_STALE_TIME: f64 = 240.0;
+const BURNING_MARGIN: f64 = 0.1;
+const STEAM_RATE: f64 = 0.8;
+const FRESHNESS_PENALTY: f64 = 0.8;
+const DONENESS_TOLERANCE: f64 = 0.1;
 const MIN_HEAT: f64 = 0.03;
 const PERLIN_HEAT: f64 = 0.07;
-const CHOP_SPEED: f64 = 0.25;
-const CUSTOMERS_PER_SECOND: f64 = 0.1;
+const CHOP_STROKE: f64 = 40.0;
+const CHOP_CUTS: f64 = 10.0;
+const CHOP_RHYTHM: f64 = 0.35;
+const CHOP_RAISED: f64 = 0.2;
+const UNEVEN_CUT: f64 = 0.15;
+const UNEVEN_LIMIT: f64 = 0.6;
+const STIR_DISTANCE: f64 = 300.0;
+const SQUEEZE_RATE: f64 = 30.0;
+const SQUIRT_RADIUS: f64 = 3.0;
+const SQUIRT_BLOBS: f64 = 30.0;
+const SQUIRT_SPACING: f64 = 3.0;
+const SQUIRT_ROWS: f64 = 8.0;
+const TOAST_SPEED: f64 = 0.8;
+const CLOCK_RADIUS: f64 = 26.0;
+const CLOCK_TEXT: f64 = 11.0;
 const ORDER_OFFSET: [f64; 2] = [0.0, 90.0];
+const FLAME_SPACING: f64 = 8.0;
 const QUEUE_SPACING: f64 = 130.0;
 const QUEUE_SPEED: f64 = 100.0;
+const TICKET_CELL: f64 = 40.0;
+const TICKET_SCALE: f64 = 0.6;
+const RAIL_HEIGHT: f64 = 26.0;
+const RAIL_ICON: f64 = 11.0;
+const RAIL_GAP: f64 = 4.0;
+const RAIL_OVERFLOW: f64 = 24.0;
+const RAIL_OVERFLOW_TEXT: f64 = 11.0;
+const GROUP_PATIENCE: f64 = 40.0;
 
 #[derive(PartialEq, Eq, Clone, Copy)]
 pub enum Topping {
@@ -76,33 +168,315 @@ pub enum Topping {
     Condiment(Condiment),
 }
 
-#[derive(PartialEq, Eq, Clone, Copy)]
+#[derive(PartialEq, Eq, Clone, Copy, Deserialize)]
 pub enum Filling {
     Sausage,
     VeggiePatty,
+    Bacon,
+    Egg,
+    Halloumi,
 }
 
-#[derive(PartialEq, Eq, Clone, Copy)]
+impl Filling {
+    /// Identifies the filling in message ids
+    pub fn id(&self) -> &'static str {
+        match self {
+            Filling::Sausage => "sausage",
+            Filling::VeggiePatty => "veggie_patty",
+            Filling::Bacon => "bacon",
+            Filling::Egg => "egg",
+            Filling::Halloumi => "halloumi",
+        }
+    }
+
+    fn size(&self) -> [f64; 2] {
+        match self {
+            Filling::Sausage => SAUSAGE_SIZE,
+            Filling::VeggiePatty => PATTY_SIZE,
+            Filling::Bacon => BACON_SIZE,
+            Filling::Egg => EGG_SIZE,
+            Filling::Halloumi => HALLOUMI_SIZE,
+        }
+    }
+
+    fn cooked_size(&self, cooked: f64) -> [f64; 2] {
+        let size = self.size();
+        match self {
+            // Bacon rashers shrink as the fat renders out
+            Filling::Bacon => {
+                let shrink = cooked.clamp(0.0, 1.2);
+                [size[0] * (1.0 - 0.1 * shrink), size[1] * (1.0 - 0.25 * shrink)]
+            },
+            _ => size,
+        }
+    }
+
+    /// Rate at which a side cooks per unit heat, given how cooked it already is
+    fn cook_rate(&self, cooked: f64) -> f64 {
+        match self {
+            Filling::Sausage => 1.0,
+            Filling::VeggiePatty => 0.5,
+            // Bacon speeds up as it crisps
+            Filling::Bacon => 0.9 + 0.6 * cooked.clamp(0.0, 1.0),
+            // The white sets quickly, then the underside slowly browns
+            Filling::Egg => if cooked < 0.6 { 1.2 } else { 0.6 },
+            // Halloumi colours gently but burns fast once golden
+            Filling::Halloumi => if cooked < 1.0 { 0.8 } else { 2.0 },
+        }
+    }
+
+    /// Fraction of the hotplate's heat that reaches the upper side
+    fn top_heat(&self) -> f64 {
+        match self {
+            Filling::Egg => 0.6,
+            _ => 0.0,
+        }
+    }
+
+    /// Whether picking the filling up turns it over
+    fn flips(&self) -> bool {
+        !matches!(self, Filling::Egg)
+    }
+
+    /// How done each side that customers judge is, given how cooked the less and more cooked sides are.
+    /// An egg is never turned over, so only its browned underside counts, as long as the top has set
+    fn done_sides(&self, cooked: [f64; 2]) -> Vec<f64> {
+        if self.flips() {
+            cooked.to_vec()
+        } else {
+            vec![cooked[1]]
+        }
+    }
+
+    /// Whether two of this filling can share a bread side by side
+    fn pairs(&self) -> bool {
+        matches!(self, Filling::Sausage | Filling::Bacon)
+    }
+
+    fn vegetarian(&self) -> bool {
+        matches!(self, Filling::VeggiePatty | Filling::Egg | Filling::Halloumi)
+    }
+
+    fn burnt(&self) -> f64 {
+        match self {
+            Filling::Halloumi => 1.2,
+            _ => 1.4,
+        }
+    }
+
+    /// Doneness below which eating it will make the customer sick
+    fn undercooked(&self) -> Option<f64> {
+        match self {
+            Filling::Sausage => Some(0.7),
+            Filling::Bacon => Some(0.5),
+            Filling::Egg => Some(0.4),
+            _ => None,
+        }
+    }
+
+    fn gradient(&self) -> [([f32; 4], f32); 3] {
+        match self {
+            Filling::Sausage => [(PINK, 0.0), (BROWN, 1.0), (BLACK, 1.4)],
+            Filling::VeggiePatty => [(YELLOW, 0.0), (ORANGE, 1.0), (BLACK, 1.4)],
+            Filling::Bacon => [(BACON, 0.0), (CRISPY_BACON, 1.0), (BLACK, 1.4)],
+            Filling::Egg => [(RAW_EGG, 0.0), (WHITE, 0.6), (WHITE, 1.4)],
+            Filling::Halloumi => [(HALLOUMI, 0.0), (GOLDEN, 1.0), (BLACK, 1.2)],
+        }
+    }
+
+    /// A small picture of the cooked filling, for order tickets
+    fn draw_icon(&self, pos: [f64; 2], context: Context, graphics: &mut G) {
+        let colour = interpolate_colour(&self.gradient(), 1.0);
+        match self {
+            Filling::Sausage | Filling::Bacon => rounded_rectangle(colour,
+                                                                   [pos[0] - 2.0, pos[1] - 7.0, 4.0, 14.0],
+                                                                   2.0,
+                                                                   context.transform,
+                                                                   graphics),
+            Filling::Egg => {
+                piston_window::ellipse(colour,
+                                       Rectangle::centered(pos, [10.0, 10.0]).as_floats(),
+                                       context.transform,
+                                       graphics);
+                piston_window::ellipse(YOLK,
+                                       Rectangle::centered(pos, [4.0, 4.0]).as_floats(),
+                                       context.transform,
+                                       graphics);
+            },
+            _ => piston_window::ellipse(colour,
+                                        Rectangle::centered(pos, [10.0, 10.0]).as_floats(),
+                                        context.transform,
+                                        graphics),
+        }
+    }
+
+    fn tray_size(&self) -> [f64; 2] {
+        match self {
+            Filling::Sausage => SAUSAGE_TRAY,
+            Filling::VeggiePatty => PATTY_TRAY,
+            Filling::Bacon => BACON_TRAY,
+            Filling::Egg => EGG_TRAY,
+            Filling::Halloumi => HALLOUMI_TRAY,
+        }
+    }
+
+    /// Offsets from the centre of the tray at which raw fillings are displayed
+    fn tray_slots(&self) -> Vec<[f64; 2]> {
+        let size = self.size();
+        match self {
+            Filling::Sausage => [-2.5, -1.5, -0.5, 0.5, 1.5, 2.5].iter()
+                .map(|x| [x * (size[0] + 3.0), 0.0])
+                .collect(),
+            Filling::Bacon => [-1.5, -0.5, 0.5, 1.5].iter()
+                .map(|x| [x * (size[0] + 3.0), 0.0])
+                .collect(),
+            Filling::Egg => [-1.0, 0.0, 1.0].iter()
+                .flat_map(|x| [[x * 20.0, -12.0], [x * 20.0, 12.0]].to_vec())
+                .collect(),
+            Filling::VeggiePatty | Filling::Halloumi => vec![
+                [0.0, -0.5 * (size[1] + 3.0)],
+                [0.0, 0.5 * (size[1] + 3.0)],
+            ],
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Deserialize)]
 pub enum Condiment {
     Sauce,
     Mustard,
+    Barbecue,
+    Aioli,
+    Chilli,
+    SweetChilli,
+    Cheese,
+    Coleslaw,
+}
+
+/// What a condiment is served from
+#[derive(PartialEq, Eq, Clone, Copy, Deserialize)]
+pub enum Dispenser {
+    /// Squeezed onto the bread from a bottle
+    Bottle,
+    /// Taken out of a tub by the pinch and sprinkled on top
+    Tub,
+}
+
+const CONDIMENT_INFO: &str = include_str!("../assets/condiments.toml");
+
+static CONDIMENTS: LazyLock<BTreeMap<Condiment, CondimentInfo>> = LazyLock::new(|| load_condiments(CONDIMENT_INFO));
+
+fn load_condiments(data: &str) -> BTreeMap<Condiment, CondimentInfo> {
+    toml::from_str(data).expect("Invalid condiments")
+}
+
+#[derive(Deserialize)]
+struct CondimentInfo {
+    colour: [f32; 4],
+    /// Vertical offset of the squirt from the middle of the bread, as a fraction of its half-width
+    placement: f64,
+    dispenser: Dispenser,
+    /// How often customers ask for it, relative to the others from the same kind of dispenser
+    popularity: f64,
 }
 
 impl Condiment {
+    pub const ALL: [Condiment; 8] = [
+        Condiment::Sauce,
+        Condiment::Mustard,
+        Condiment::Barbecue,
+        Condiment::Aioli,
+        Condiment::Chilli,
+        Condiment::SweetChilli,
+        Condiment::Cheese,
+        Condiment::Coleslaw,
+    ];
+
+    fn info(&self) -> &'static CondimentInfo {
+        &CONDIMENTS[self]
+    }
+
     fn colour(&self) -> [f32; 4] {
-        match self {
-            Condiment::Sauce => [0.95, 0.1, 0.0, 1.0],
-            Condiment::Mustard => [0.9, 0.85, 0.0, 1.0],
+        self.info().colour
+    }
+
+    pub fn dispenser(&self) -> Dispenser {
+        self.info().dispenser
+    }
+
+    /// Pick between the condiments on the menu from one kind of dispenser, weighted by how popular each is
+    fn choose<R: Rng>(dispenser: Dispenser, menu: &Menu, except: &[Condiment], rng: &mut R) -> Option<Condiment> {
+        let options: Vec<Condiment> = menu.condiments.iter()
+            .cloned()
+            .filter(|c| c.dispenser() == dispenser && !except.contains(c))
+            .collect();
+        let weights = WeightedIndex::new(options.iter().map(|c| c.info().popularity)).ok()?;
+        Some(options[rng.sample(weights)])
+    }
+}
+
+/// Cooked food cools down once it is off the heat, and goes stale if left sitting cold
+#[derive(Clone, Copy)]
+pub struct Temperature {
+    warmth: f64,
+    stale: f64,
+}
+
+impl Temperature {
+    fn new() -> Temperature {
+        Temperature{
+            warmth: 1.0,
+            stale: 0.0,
+        }
+    }
+
+    fn update(&mut self, dt: f64, heat: f64, cooked: f64) {
+        if heat > 0.0 {
+            self.warmth = (self.warmth + dt * WARM_UP_SPEED).min(1.0);
+            // Food held warm rather than cooked still dries out, just slower than once it's gone cold
+            if heat <= WARMING_HEAT && cooked > 0.5 {
+                self.stale += dt / HELD_STALE_TIME;
+            }
+        } else {
+            self.warmth = (self.warmth - dt / COOL_TIME).max(0.0);
+            if cooked > 0.5 {
+                self.stale += dt * (1.0 - self.warmth) / STALE_TIME;
+            }
+        }
+    }
+
+    fn freshness(&self) -> f64 {
+        self.warmth * (1.0 - self.stale.min(1.0))
+    }
+
+    /// Fade the colour of food that has gone stale
+    fn colour(&self, colour: [f32; 4]) -> [f32; 4] {
+        let stale = [STALE[0], STALE[1], STALE[2], colour[3]];
+        interpolate_colour(&[(colour, 0.0), (stale, 1.0)], 0.4 * self.stale.min(1.0) as f32)
+    }
+
+    /// Warm cooked food off the heat gives off a little steam
+    fn steam(&self, dt: f64, heat: f64, cooked: f64, bounds: Rectangle) -> Vec<Rc<RefCell<dyn Entity>>> {
+        if heat == 0.0 && cooked > 0.5 && rand::random::<f64>() < dt * self.warmth * STEAM_RATE {
+            let bounds = bounds.as_floats();
+            vec![Rc::new(RefCell::new(Smoke::new([
+                bounds[0] + rand::random::<f64>() * bounds[2],
+                bounds[1] + rand::random::<f64>() * bounds[3],
+            ], 1.0)))]
+        } else {
+            vec![]
         }
     }
 }
 
 pub struct Cookable {
     pos: [f64; 2],
+    angle: f64,
     heat: f64,
     top_cooked: f64,
     bottom_cooked: f64,
     flipped: bool,
+    temperature: Temperature,
     kind: Filling,
 }
 
@@ -114,25 +488,49 @@ impl Cookable {
     pub fn with_cooked(kind: Filling, pos: [f64; 2], cooked:f64) -> Cookable {
         Cookable{
             pos,
+            angle: 0.0,
             heat: 0.0,
             top_cooked: cooked,
             bottom_cooked: cooked,
             flipped: false,
+            temperature: Temperature::new(),
             kind,
         }
     }
+
+    /// Outline before rotation
+    fn rect(&self) -> Rectangle {
+        let cooked = 0.5 * (self.top_cooked + self.bottom_cooked);
+        Rectangle::centered(self.pos, self.kind.cooked_size(cooked))
+    }
 }
 
 impl Entity for Cookable {
     fn bounds(&self) -> Rectangle {
-        match self.kind {
-            Filling::Sausage => Rectangle::centered(self.pos, SAUSAGE_SIZE),
-            Filling::VeggiePatty => Rectangle::centered(self.pos, PATTY_SIZE),
-        }
+        self.footprint().bounds()
+    }
+
+    fn shape(&self) -> Shape {
+        let shape = match self.kind {
+            Filling::Sausage | Filling::Bacon => Shape::Rectangle(self.rect()),
+            Filling::VeggiePatty => Shape::RoundedRectangle(self.rect(), 10.0),
+            Filling::Egg => Shape::Ellipse(self.rect()),
+            Filling::Halloumi => Shape::RoundedRectangle(self.rect(), 4.0),
+        };
+        Shape::Rotated(Box::new(shape), self.pos, self.angle)
+    }
+
+    fn footprint(&self) -> OrientedRectangle {
+        let cooked = 0.5 * (self.top_cooked + self.bottom_cooked);
+        OrientedRectangle::new(self.pos, self.kind.cooked_size(cooked), self.angle)
+    }
+
+    fn solid(&self) -> bool {
+        true
     }
 
     fn select(&mut self, pos: [f64; 2]) -> Selection {
-        if self.bounds().intersect_point(pos) {
+        if self.shape().contains(pos) {
             Selection::This
         } else {
             Selection::None
@@ -140,18 +538,17 @@ impl Entity for Cookable {
     }
 
     fn update(&mut self, dt: f64) -> Vec<Rc<RefCell<dyn Entity>>> {
-        self.bottom_cooked += dt * self.heat * match self.kind {
-            Filling::Sausage => 1.0,
-            Filling::VeggiePatty => 0.5,
-        };
+        self.bottom_cooked += dt * self.heat * self.kind.cook_rate(self.bottom_cooked);
+        self.top_cooked += dt * self.heat * self.kind.top_heat() * self.kind.cook_rate(self.top_cooked);
+        self.temperature.update(dt, self.heat, self.cooked()[0]);
         if rand::random::<f64>() < dt * self.heat * 20.0 {
             let bounds = self.bounds().as_floats();
             vec![Rc::new(RefCell::new(Smoke::new([
                 bounds[0] + rand::random::<f64>() * bounds[2],
                 bounds[1] + rand::random::<f64>() * bounds[3],
-            ], 0.4 * (3.8 - 3.0 * self.bottom_cooked as f32).max(0.0).min(1.0))))]
+            ], 0.4 * (3.8 - 3.0 * self.bottom_cooked as f32).clamp(0.0, 1.0))))]
         } else {
-            vec![]
+            self.temperature.steam(dt, self.heat, self.cooked()[0], self.bounds())
         }
     }
 
@@ -165,11 +562,15 @@ impl Entity for Cookable {
         self.pos = pos;
     }
 
+    fn rotate(&mut self, angle: f64) {
+        self.angle += angle;
+    }
+
     fn grab(&mut self) {
-        let tmp = self.top_cooked;
-        self.top_cooked = self.bottom_cooked;
-        self.bottom_cooked = tmp;
-        self.flipped = !self.flipped;
+        if self.kind.flips() {
+            std::mem::swap(&mut self.top_cooked, &mut self.bottom_cooked);
+            self.flipped = !self.flipped;
+        }
         self.heat = 0.0;
     }
 
@@ -184,18 +585,32 @@ impl Entity for Cookable {
         self.heat = heat;
     }
 
+    fn freshness(&self) -> f64 {
+        self.temperature.freshness()
+    }
+
+    fn warning(&self) -> Option<&'static str> {
+        if self.heat > 0.0 && self.bottom_cooked > self.kind.burnt() - BURNING_MARGIN {
+            Some("hint_burning")
+        } else {
+            None
+        }
+    }
+
     fn draw(&self, context: Context, graphics: &mut G) {
+        let color = self.temperature.colour(interpolate_colour(&self.kind.gradient(), self.top_cooked as f32));
+        let bounds = self.rect().as_floats();
+        let context = context.trans(self.pos[0], self.pos[1])
+            .rot_rad(self.angle)
+            .trans(-self.pos[0], -self.pos[1]);
         match self.kind {
             Filling::Sausage => {
-                let color = interpolate_colour(&[(PINK, 0.0), (BROWN, 1.0), (BLACK, 1.4)], self.top_cooked as f32);
                 piston_window::rectangle(color,
-                                         self.bounds().as_floats(),
+                                         bounds,
                                          context.transform,
                                          graphics);
             },
             Filling::VeggiePatty => {
-                let color = interpolate_colour(&[(YELLOW, 0.0), (ORANGE, 1.0), (BLACK, 1.4)], self.top_cooked as f32);
-                let bounds = self.bounds().as_floats();
                 rounded_rectangle(color,
                                   bounds,
                                   10.0,
@@ -215,6 +630,61 @@ impl Entity for Cookable {
                                            graphics);
                 }
             },
+            Filling::Bacon => {
+                let fat = interpolate_colour(&[(FAT, 0.0), (GOLDEN, 1.0), (BLACK, 1.4)], self.top_cooked as f32);
+                let segments = 6;
+                let h = bounds[3] / segments as f64;
+                for i in 0..segments {
+                    // Rashers curl a little more as they crisp
+                    let wave = (1.0 + 2.0 * self.top_cooked.clamp(0.0, 1.0)) * (i as f64 * 1.3).sin();
+                    let x = bounds[0] + wave;
+                    let y = bounds[1] + i as f64 * h;
+                    piston_window::rectangle(color,
+                                             [x, y, bounds[2], h + 0.5],
+                                             context.transform,
+                                             graphics);
+                    piston_window::rectangle(fat,
+                                             [x + 0.2 * bounds[2], y, 0.15 * bounds[2], h + 0.5],
+                                             context.transform,
+                                             graphics);
+                    piston_window::rectangle(fat,
+                                             [x + 0.75 * bounds[2], y, 0.2 * bounds[2], h + 0.5],
+                                             context.transform,
+                                             graphics);
+                }
+            },
+            Filling::Egg => {
+                // The underside shows through as a crispy frill around the edge
+                let frill = interpolate_colour(&[(RAW_EGG, 0.0), (WHITE, 0.8), (GOLDEN, 1.2), (BLACK, 1.6)], self.bottom_cooked as f32);
+                piston_window::ellipse(frill,
+                                       bounds,
+                                       context.transform,
+                                       graphics);
+                piston_window::ellipse(color,
+                                       [bounds[0] + 3.0, bounds[1] + 3.0, bounds[2] - 6.0, bounds[3] - 6.0],
+                                       context.transform,
+                                       graphics);
+                let yolk = interpolate_colour(&[(YOLK, 0.6), (SET_YOLK, 1.2)], self.top_cooked as f32);
+                piston_window::ellipse(yolk,
+                                       Rectangle::centered([self.pos[0] + 3.0, self.pos[1] - 2.0], [16.0, 16.0]).as_floats(),
+                                       context.transform,
+                                       graphics);
+            },
+            Filling::Halloumi => {
+                rounded_rectangle(color,
+                                  bounds,
+                                  4.0,
+                                  context.transform,
+                                  graphics);
+                // Grill marks on the side that has been face down
+                let marks = interpolate_colour(&[([BLACK[0], BLACK[1], BLACK[2], 0.0], 0.3), ([BLACK[0], BLACK[1], BLACK[2], 0.6], 1.0)], self.top_cooked as f32);
+                for x in &[0.25, 0.5, 0.75] {
+                    piston_window::rectangle(marks,
+                                             [bounds[0] + x * bounds[2] - 2.0, bounds[1] + 3.0, 4.0, bounds[3] - 6.0],
+                                             context.transform,
+                                             graphics);
+                }
+            },
         }
     }
 
@@ -223,31 +693,36 @@ impl Entity for Cookable {
     }
 
     fn add_to(&mut self, pos: [f64; 2], others: &[Rc<RefCell<dyn Entity>>]) -> Selection {
-        let mut other_fillings = others.iter()
-            .filter(|e| if let Some(Topping::Filling(_)) = e.borrow().topping() {
-                true
+        let other_fillings: Vec<(Rc<RefCell<dyn Entity>>, Filling)> = others.iter()
+            .filter_map(|e| match e.borrow().topping() {
+                Some(Topping::Filling(f)) => Some((e.clone(), f)),
+                _ => None,
+            })
+            .collect();
+        if self.kind == Filling::Egg {
+            // An egg sits on top of whatever else is on the bread
+            if other_fillings.iter().any(|(_, f)| *f == Filling::Egg) {
+                Selection::None
             } else {
-                false
-            });
-        match other_fillings.next() {
-            None => {
                 self.set_pos(pos);
+                self.angle = 0.0;
                 Selection::This
-            },
-            Some(f) => if Some(Topping::Filling(Filling::Sausage)) == f.borrow().topping() {
-                if self.kind == Filling::Sausage {
-                    if let None = other_fillings.next() {
-                        self.pos = [pos[0] + SAUSAGE_OFFSET, pos[1]];
-                        f.borrow_mut().set_pos([pos[0] - SAUSAGE_OFFSET, pos[1]]);
-                        Selection::This
-                    } else {
-                        Selection::None
-                    }
-                } else {
-                    Selection::None
-                }
-            } else {
-                Selection::None
+            }
+        } else {
+            let mut other_fillings = other_fillings.iter().filter(|(_, f)| *f != Filling::Egg);
+            match (other_fillings.next(), other_fillings.next()) {
+                (None, _) => {
+                    self.set_pos(pos);
+                    self.angle = 0.0;
+                    Selection::This
+                },
+                (Some((f, kind)), None) if *kind == self.kind && self.kind.pairs() => {
+                    self.pos = [pos[0] + SAUSAGE_OFFSET, pos[1]];
+                    self.angle = 0.0;
+                    f.borrow_mut().set_pos([pos[0] - SAUSAGE_OFFSET, pos[1]]);
+                    Selection::This
+                },
+                _ => Selection::None,
             }
         }
     }
@@ -256,6 +731,7 @@ impl Entity for Cookable {
 pub struct Hotplate {
     bounds: Rectangle,
     noise: noise::Perlin,
+    grid: bool,
 }
 
 impl Hotplate {
@@ -263,8 +739,15 @@ impl Hotplate {
         Hotplate{
             bounds: Rectangle::new(pos, size),
             noise: noise::Perlin::new().set_seed(seed),
+            grid: false,
         }
     }
+
+    /// Snap anything put down on the hotplate to a grid of cooking spots
+    pub fn with_grid(mut self) -> Hotplate {
+        self.grid = true;
+        self
+    }
 }
 
 impl Entity for Hotplate {
@@ -272,11 +755,49 @@ impl Entity for Hotplate {
         self.bounds
     }
 
+    fn placements(&self, pos: [f64; 2]) -> Option<Vec<[f64; 2]>> {
+        if !self.bounds.intersect_point(pos) {
+            None
+        } else if self.grid {
+            // Nearby cells, closest first
+            let bounds = self.bounds.as_floats();
+            let cell = |x: f64, i: f64| (x / GRID_CELL).floor() + i;
+            let mut cells: Vec<[f64; 2]> = (-GRID_SEARCH..=GRID_SEARCH)
+                .flat_map(|i| (-GRID_SEARCH..=GRID_SEARCH).map(move |j| (i as f64, j as f64)))
+                .map(|(i, j)| [
+                    bounds[0] + (cell(pos[0] - bounds[0], i) + 0.5) * GRID_CELL,
+                    bounds[1] + (cell(pos[1] - bounds[1], j) + 0.5) * GRID_CELL,
+                ])
+                .filter(|p| self.bounds.intersect_point(*p))
+                .collect();
+            let distance = |p: &[f64; 2]| (p[0] - pos[0]).powi(2) + (p[1] - pos[1]).powi(2);
+            cells.sort_by(|a, b| distance(a).partial_cmp(&distance(b)).unwrap());
+            Some(cells)
+        } else {
+            Some(vec![pos])
+        }
+    }
+
     fn draw(&self, context: Context, graphics: &mut G) {
         piston_window::rectangle([0.2, 0.15, 0.25, 1.0],
                                  self.bounds().as_floats(),
                                  context.transform,
                                  graphics);
+        if self.grid {
+            let bounds = self.bounds().as_floats();
+            for i in 1..(bounds[2] / GRID_CELL).ceil() as usize {
+                piston_window::rectangle(GRID_LINE,
+                                         [bounds[0] + i as f64 * GRID_CELL, bounds[1], 1.0, bounds[3]],
+                                         context.transform,
+                                         graphics);
+            }
+            for j in 1..(bounds[3] / GRID_CELL).ceil() as usize {
+                piston_window::rectangle(GRID_LINE,
+                                         [bounds[0], bounds[1] + j as f64 * GRID_CELL, bounds[2], 1.0],
+                                         context.transform,
+                                         graphics);
+            }
+        }
         // let bounds = self.bounds().as_floats();
         // for x in 0..100 {
         //     for y in 0..100 {
@@ -307,6 +828,55 @@ impl Entity for Hotplate {
     }
 }
 
+pub struct WarmingTray {
+    bounds: Rectangle,
+}
+
+impl WarmingTray {
+    pub fn new(pos: [f64; 2], size: [f64; 2]) -> WarmingTray {
+        WarmingTray{
+            bounds: Rectangle::new(pos, size),
+        }
+    }
+}
+
+impl Entity for WarmingTray {
+    fn bounds(&self) -> Rectangle {
+        self.bounds
+    }
+
+    fn placements(&self, pos: [f64; 2]) -> Option<Vec<[f64; 2]>> {
+        if self.bounds.intersect_point(pos) {
+            Some(vec![pos])
+        } else {
+            None
+        }
+    }
+
+    fn heat(&self, pos: [f64; 2]) -> f64 {
+        if self.bounds.intersect_point(pos) {
+            WARMING_HEAT
+        } else {
+            0.0
+        }
+    }
+
+    fn draw(&self, context: Context, graphics: &mut G) {
+        let bounds = self.bounds().as_floats();
+        rounded_rectangle(DARK_GREY,
+                          bounds,
+                          4.0,
+                          context.transform,
+                          graphics);
+        for y in &[0.3, 0.7] {
+            piston_window::rectangle(ELEMENT,
+                                     [bounds[0] + 6.0, bounds[1] + y * bounds[3] - 1.5, bounds[2] - 12.0, 3.0],
+                                     context.transform,
+                                     graphics);
+        }
+    }
+}
+
 pub struct Table {
     bounds: Rectangle,
 }
@@ -362,13 +932,21 @@ impl Entity for Table {
 
 pub struct Bread {
     pos: [f64; 2],
+    heat: f64,
+    toasted: f64,
     toppings: Vec<Rc<RefCell<dyn Entity>>>,
 }
 
 impl Bread {
     pub fn new(pos: [f64; 2]) -> Bread {
+        Bread::with_toasted(pos, 0.0)
+    }
+
+    pub fn with_toasted(pos: [f64; 2], toasted: f64) -> Bread {
         Bread{
             pos,
+            heat: 0.0,
+            toasted,
             toppings: Vec::new(),
         }
     }
@@ -378,18 +956,65 @@ impl Clone for Bread {
     fn clone(&self) -> Bread {
         Bread{
             pos: self.pos,
+            heat: self.heat,
+            toasted: self.toasted,
             toppings: self.toppings.clone(),
         }
     }
 }
 
+impl Bread {
+    /// How many of `topping` are on the bread
+    pub fn count(&self, topping: Topping) -> usize {
+        self.toppings.iter().filter(|t| t.borrow().topping() == Some(topping)).count()
+    }
+
+    /// Lay the toppings out again after one is taken off, so a lone sausage moves back to the middle
+    fn lay_out(&mut self) {
+        for topping in std::mem::take(&mut self.toppings) {
+            let placed = topping.borrow_mut().add_to(self.pos, &self.toppings);
+            match placed {
+                Selection::New(new) => self.toppings.push(new),
+                // Anything that won't move stays where it is rather than falling off the bread
+                Selection::This | Selection::None => self.toppings.push(topping),
+            }
+        }
+        self.set_heat(self.heat);
+    }
+
+    /// Whether the condiment is layered beneath the onion, if the bread has both
+    fn under_onion(&self, condiment: Condiment) -> Option<bool> {
+        let condiment = self.toppings.iter().position(|t| t.borrow().topping() == Some(Topping::Condiment(condiment)))?;
+        let onion = self.toppings.iter().position(|t| t.borrow().topping() == Some(Topping::Onion))?;
+        Some(condiment < onion)
+    }
+}
+
 impl Entity for Bread {
     fn bounds(&self) -> Rectangle {
         Rectangle::centered(self.pos, BREAD_SIZE)
     }
 
+    fn solid(&self) -> bool {
+        true
+    }
+
     fn select(&mut self, pos: [f64; 2]) -> Selection {
-        if self.bounds().intersect_point(pos) {
+        if self.shape().contains(pos) {
+            // Toppings are picked off from the top of the stack down
+            for i in (0..self.toppings.len()).rev() {
+                let selection = self.toppings[i].borrow_mut().select(pos);
+                match selection {
+                    Selection::None => {},
+                    // Only part of the topping was picked up, like a portion off a pile of onions
+                    Selection::New(part) => return Selection::New(part),
+                    Selection::This => {
+                        let topping = self.toppings.remove(i);
+                        self.lay_out();
+                        return Selection::New(topping);
+                    },
+                }
+            }
             Selection::This
         } else {
             Selection::None
@@ -409,13 +1034,45 @@ impl Entity for Bread {
         self.drag(self.pos, pos);
     }
 
+    fn update(&mut self, dt: f64) -> Vec<Rc<RefCell<dyn Entity>>> {
+        self.toasted += dt * self.heat * TOAST_SPEED;
+        // Toppings keep cooling down while they sit on the bread
+        let mut new: Vec<_> = self.toppings.iter()
+            .flat_map(|t| t.borrow_mut().update(dt))
+            .collect();
+        if rand::random::<f64>() < dt * self.heat * 20.0 {
+            let bounds = self.bounds().as_floats();
+            new.push(Rc::new(RefCell::new(Smoke::new([
+                bounds[0] + rand::random::<f64>() * bounds[2],
+                bounds[1] + rand::random::<f64>() * bounds[3],
+            ], 0.4 * (3.8 - 3.0 * self.toasted as f32).clamp(0.0, 1.0)))));
+        }
+        new
+    }
+
+    fn grab(&mut self) {
+        self.set_heat(0.0);
+    }
+
+    fn set_heat(&mut self, heat: f64) {
+        self.heat = heat;
+        // The bread keeps its toppings warm, but they won't cook through it
+        for topping in &self.toppings {
+            topping.borrow_mut().set_heat(heat.min(WARMING_HEAT));
+        }
+    }
+
+    fn cooked(&self) -> [f64; 2] {
+        [self.toasted, self.toasted]
+    }
+
     fn draw(&self, context: Context, graphics: &mut G) {
         let inner_size = Rectangle::centered(self.pos, [BREAD_SIZE[0] - 6.0, BREAD_SIZE[1] - 6.0]);
-        piston_window::rectangle([194.0 / 255.0, 153.0 / 255.0, 26.0 / 255.0, 1.0],
+        piston_window::rectangle(interpolate_colour(&[(CRUST, 0.0), (BROWN, 1.0), (BLACK, 1.4)], self.toasted as f32),
                                  self.bounds().as_floats(),
                                  context.transform,
                                  graphics);
-        piston_window::rectangle([255.0 / 255.0, 246.0 / 255.0, 206.0 / 255.0, 1.0],
+        piston_window::rectangle(interpolate_colour(&[(CRUMB, 0.0), (TOAST, 1.0), (BLACK, 1.4)], self.toasted as f32),
                                  inner_size.as_floats(),
                                  context.transform,
                                  graphics);
@@ -435,9 +1092,32 @@ impl Entity for Bread {
             },
             _ => {},
         }
+        self.set_heat(self.heat);
         res
     }
 
+    /// Sauce squeezed onto the bread joins any squirt of the same condiment already on it
+    fn use_tool(&mut self, tool: Tool, pos: [f64; 2]) -> bool {
+        let condiment = match tool {
+            Tool::Bottle(condiment) if self.shape().contains(pos) => condiment,
+            _ => return false,
+        };
+        for topping in &self.toppings {
+            let mut topping = topping.borrow_mut();
+            if topping.topping() != Some(Topping::Condiment(condiment)) {
+                continue;
+            }
+            if let Some(squirt) = (*topping).as_any_mut().downcast_mut::<Squirt>() {
+                squirt.add(pos);
+                return true;
+            }
+        }
+        let mut squirt = Squirt::new(condiment, self.pos);
+        squirt.add(pos);
+        self.toppings.push(Rc::new(RefCell::new(squirt)));
+        true
+    }
+
     fn order(&self) -> Option<&Bread> {
         Some(self)
     }
@@ -459,7 +1139,7 @@ impl Entity for Loaf {
     }
 
     fn select(&mut self, pos: [f64; 2]) -> Selection {
-        if self.bounds().intersect_point(pos) {
+        if self.shape().contains(pos) {
             Selection::New(Rc::new(RefCell::new(Bread::new(pos))))
         } else {
             Selection::None
@@ -473,104 +1153,168 @@ impl Entity for Loaf {
     fn draw(&self, context: Context, graphics: &mut G) {
         let inner_size = Rectangle::centered([self.pos[0], self.pos[1] + LOAF_SIZE[1] / 2.0 - 3.0 - 0.6 * BREAD_SIZE[1] / 2.0],
                                              [LOAF_SIZE[0] - 6.0, 0.7 * BREAD_SIZE[1] - 6.0]);
-        piston_window::rectangle([194.0 / 255.0, 153.0 / 255.0, 26.0 / 255.0, 1.0],
+        piston_window::rectangle(CRUST,
                                  self.bounds().as_floats(),
                                  context.transform,
                                  graphics);
-        piston_window::rectangle([255.0 / 255.0, 246.0 / 255.0, 206.0 / 255.0, 1.0],
+        piston_window::rectangle(CRUMB,
                                  inner_size.as_floats(),
                                  context.transform,
                                  graphics);
     }
 }
 
-pub struct SausageTray {
+pub struct Tray {
     pos: [f64; 2],
+    kind: Filling,
+    samples: Vec<Cookable>,
 }
 
-impl SausageTray {
-    pub fn new(pos: [f64; 2]) -> SausageTray {
-        SausageTray{pos}
+impl Tray {
+    pub fn new(kind: Filling, pos: [f64; 2]) -> Tray {
+        let samples = kind.tray_slots().iter()
+            .enumerate()
+            .map(|(i, offset)| {
+                let mut sample = Cookable::new(kind, [pos[0] + offset[0], pos[1] + offset[1]]);
+                sample.flipped = i % 2 == 1;
+                sample
+            })
+            .collect();
+        Tray{
+            pos,
+            kind,
+            samples,
+        }
     }
 }
 
-impl Entity for SausageTray {
+impl Entity for Tray {
     fn bounds(&self) -> Rectangle {
-        Rectangle::centered(self.pos, SAUSAGE_TRAY)
+        Rectangle::centered(self.pos, self.kind.tray_size())
+    }
+
+    fn shape(&self) -> Shape {
+        Shape::RoundedRectangle(self.bounds(), 3.0)
     }
 
     fn select(&mut self, pos: [f64; 2]) -> Selection {
-        if self.bounds().intersect_point(pos) {
-            Selection::New(Rc::new(RefCell::new(Cookable::new(Filling::Sausage, pos))))
+        if self.shape().contains(pos) {
+            Selection::New(Rc::new(RefCell::new(Cookable::new(self.kind, pos))))
         } else {
             Selection::None
         }
     }
 
-    fn set_pos(&mut self, pos: [f64; 2]) {
-        self.pos = pos;
-    }
-
     fn draw(&self, context: Context, graphics: &mut G) {
-        rounded_rectangle(DARK_GREY,
+        rounded_rectangle(if self.kind == Filling::Egg { CARTON } else { DARK_GREY },
                           self.bounds().as_floats(),
                           3.0,
                           context.transform,
                           graphics);
-        for x in &[-2.5, -1.5, -0.5, 0.5, 1.5, 2.5] {
-            piston_window::rectangle(PINK,
-                                     Rectangle::centered([self.pos[0] + x * (SAUSAGE_SIZE[0] + 3.0), self.pos[1]], SAUSAGE_SIZE).as_floats(),
-                                     context.transform,
-                                     graphics);
+        for sample in &self.samples {
+            if self.kind == Filling::Egg {
+                // Eggs stay in their shells until they hit the hotplate
+                piston_window::ellipse(SHELL,
+                                       Rectangle::centered(sample.pos, [16.0, 20.0]).as_floats(),
+                                       context.transform,
+                                       graphics);
+            } else {
+                sample.draw(context, graphics);
+            }
         }
     }
 }
 
-pub struct PattyTray {
+pub struct Spatula {
     pos: [f64; 2],
-    patties: [Cookable; 2],
+    heading: Option<[f64; 2]>,
+    turned: f64,
+    travelled: f64,
+    stir: Option<[f64; 2]>,
 }
 
-impl PattyTray {
-    pub fn new(pos: [f64; 2]) -> PattyTray {
-        let mut patties = [
-            Cookable::new(Filling::VeggiePatty, [pos[0], pos[1] - 0.5 * (PATTY_SIZE[1] + 3.0)]),
-            Cookable::new(Filling::VeggiePatty, [pos[0], pos[1] + 0.5 * (PATTY_SIZE[1] + 3.0)]),
-        ];
-        patties[1].flipped = true;
-        PattyTray{
+impl Spatula {
+    pub fn new(pos: [f64; 2]) -> Spatula {
+        Spatula{
             pos,
-            patties,
+            heading: None,
+            turned: 0.0,
+            travelled: 0.0,
+            stir: None,
         }
     }
 }
 
-impl Entity for PattyTray {
+impl Entity for Spatula {
     fn bounds(&self) -> Rectangle {
-        Rectangle::centered(self.pos, PATTY_TRAY)
+        Rectangle::new([self.pos[0] - 13.0, self.pos[1] - 11.0], [26.0, 72.0])
+    }
+
+    fn shape(&self) -> Shape {
+        Shape::Union(vec![
+            Shape::Rectangle(Rectangle::centered(self.pos, [26.0, 22.0])),
+            Shape::Rectangle(Rectangle::new([self.pos[0] - 3.0 - PICK_TOLERANCE, self.pos[1] + 11.0], [6.0 + 2.0 * PICK_TOLERANCE, 50.0])),
+        ])
     }
 
     fn select(&mut self, pos: [f64; 2]) -> Selection {
-        if self.bounds().intersect_point(pos) {
-            Selection::New(Rc::new(RefCell::new(Cookable::new(Filling::VeggiePatty, pos))))
+        if self.shape().contains(pos) {
+            Selection::This
         } else {
             Selection::None
         }
     }
 
+    fn grab(&mut self) {
+        self.heading = None;
+        self.turned = 0.0;
+        self.travelled = 0.0;
+    }
+
+    fn drag(&mut self, from: [f64; 2], to: [f64; 2]) {
+        let d = [to[0] - from[0], to[1] - from[1]];
+        let len = (d[0] * d[0] + d[1] * d[1]).sqrt();
+        self.pos = [self.pos[0] + d[0], self.pos[1] + d[1]];
+        if len < 1.0 {
+            return;
+        }
+        // Add up how far the blade has turned, so a full small circle counts as a stir
+        let heading = [d[0] / len, d[1] / len];
+        if let Some(last) = self.heading {
+            let cross = last[0] * heading[1] - last[1] * heading[0];
+            let dot = last[0] * heading[0] + last[1] * heading[1];
+            self.turned += cross.atan2(dot);
+        }
+        self.heading = Some(heading);
+        self.travelled += len;
+        if self.turned.abs() >= 2.0 * std::f64::consts::PI {
+            self.stir = Some(self.pos);
+            self.turned = 0.0;
+            self.travelled = 0.0;
+        } else if self.travelled > STIR_DISTANCE {
+            self.turned = 0.0;
+            self.travelled = 0.0;
+        }
+    }
+
     fn set_pos(&mut self, pos: [f64; 2]) {
-        // self.pos = pos;
+        self.pos = pos;
+    }
+
+    fn take_tool_use(&mut self) -> Option<(Tool, Vec<[f64; 2]>)> {
+        self.stir.take().map(|pos| (Tool::Spatula, vec![pos]))
     }
 
     fn draw(&self, context: Context, graphics: &mut G) {
-        rounded_rectangle(DARK_GREY,
-                          self.bounds().as_floats(),
+        piston_window::rectangle([0.1, 0.0, 0.0, 1.0],
+                                 [self.pos[0] - 3.0, self.pos[1] + 11.0, 6.0, 50.0],
+                                 context.transform,
+                                 graphics);
+        rounded_rectangle([145.0 / 255.0, 145.0 / 255.0, 145.0 / 255.0, 1.0],
+                          Rectangle::centered(self.pos, [26.0, 22.0]).as_floats(),
                           3.0,
                           context.transform,
                           graphics);
-        for patty in &self.patties {
-            patty.draw(context, graphics)
-        }
     }
 }
 
@@ -613,15 +1357,44 @@ impl Entity for Smoke {
 pub struct ChoppingBoard {
     pos: [f64; 2],
     progress: f64,
+    portions: usize,
     onions: Vec<Onion>,
+    knife: f64,
+    raised: bool,
+    since_cut: f64,
+    unevenness: f64,
 }
 
 impl ChoppingBoard {
-    pub fn new(pos: [f64; 2]) -> ChoppingBoard {
+    /// A board that chops `portions` onions' worth of pieces at a time, at least one
+    pub fn new(pos: [f64; 2], portions: usize) -> ChoppingBoard {
         ChoppingBoard{
             pos,
             progress: 0.0,
-            onions: vec![Onion::new(pos), Onion::new(pos), Onion::new(pos), Onion::new(pos)],
+            portions: portions.max(1),
+            onions: vec![],
+            knife: 0.0,
+            raised: true,
+            since_cut: f64::INFINITY,
+            unevenness: 0.0,
+        }
+    }
+
+    /// Bring the knife down through the onion, faster the closer the cut is to a steady rhythm
+    fn cut(&mut self) {
+        let rhythm = if self.since_cut.is_finite() {
+            (-((self.since_cut - CHOP_RHYTHM) / CHOP_RHYTHM).powi(2)).exp()
+        } else {
+            0.5
+        };
+        self.since_cut = 0.0;
+        self.progress = (self.progress + (0.5 + rhythm) / CHOP_CUTS).min(1.0);
+        if rand::random::<f64>() > rhythm {
+            self.unevenness += UNEVEN_CUT;
+        }
+        if self.progress >= 1.0 {
+            let unevenness = self.unevenness.min(UNEVEN_LIMIT);
+            self.onions = (0..self.portions).map(|_| Onion::chopped(self.pos, unevenness)).collect();
         }
     }
 }
@@ -644,8 +1417,8 @@ fn knife(centre: [f64; 2], transform: [[f64; 3]; 2], graphics: &mut G) {
 }
 
 pub fn interpolate_path(points: &[([f64; 2], f64)], point: f64) -> [f64; 2] {
-    let lower = points.iter().filter(|(_, x)| *x < point).last();
-    let upper = points.iter().filter(|(_, x)| *x >= point).next();
+    let lower = points.iter().rfind(|(_, x)| *x < point);
+    let upper = points.iter().find(|(_, x)| *x >= point);
 
     match (lower, upper) {
         (None, None) => panic!("Gradient missing reference points"),
@@ -667,13 +1440,13 @@ impl Entity for ChoppingBoard {
     }
 
     fn select(&mut self, pos: [f64; 2]) -> Selection {
-        if self.bounds().intersect_point(pos) {
+        if self.shape().contains(pos) {
             if self.progress < 1.0 {
                 Selection::This
             } else if let Some(onion) = self.onions.pop() {
-                if self.onions.len() == 0 {
+                if self.onions.is_empty() {
                     self.progress = 0.0;
-                    self.onions = vec![Onion::new(pos), Onion::new(pos), Onion::new(pos), Onion::new(pos)];
+                    self.unevenness = 0.0;
                 }
                 Selection::New(Rc::new(RefCell::new(onion)))
             } else {
@@ -685,7 +1458,32 @@ impl Entity for ChoppingBoard {
     }
 
     fn update_selected(&mut self, dt: f64) {
-        self.progress = (self.progress + dt * CHOP_SPEED).min(1.0);
+        self.since_cut += dt;
+    }
+
+    /// Whether there are chopped onions waiting to be picked up
+    fn ready(&self) -> bool {
+        self.progress >= 1.0 && !self.onions.is_empty()
+    }
+
+    fn drop(&mut self) {
+        self.knife = 0.0;
+        self.raised = true;
+        self.since_cut = f64::INFINITY;
+    }
+
+    /// Each stroke down the board cuts the onion, once the knife has been lifted again
+    fn drag(&mut self, from: [f64; 2], to: [f64; 2]) {
+        if self.progress >= 1.0 {
+            return;
+        }
+        self.knife = (self.knife + (to[1] - from[1]) / CHOP_STROKE).clamp(0.0, 1.0);
+        if self.knife <= CHOP_RAISED {
+            self.raised = true;
+        } else if self.knife >= 1.0 && self.raised {
+            self.raised = false;
+            self.cut();
+        }
     }
 
     fn draw(&self, context: Context, graphics: &mut G) {
@@ -719,32 +1517,37 @@ impl Entity for ChoppingBoard {
             }
         }
 
-        knife(interpolate_path(
-            &[(centre, 0.0),
-              ([centre[0] - 30.0, centre[1] + 30.0], 0.1),
-              ([centre[0] + 30.0, centre[1] + 30.0], 0.2),
-              ([centre[0] - 30.0, centre[1] + 35.0], 0.35),
-              ([centre[0] + 30.0, centre[1] + 35.0], 0.45),
-              ([centre[0] - 30.0, centre[1] + 40.0], 0.6),
-              ([centre[0] + 30.0, centre[1] + 40.0], 0.7),
-              ([centre[0] - 30.0, centre[1] + 45.0], 0.85),
-              ([centre[0] + 30.0, centre[1] + 45.0], 0.95),
-              (centre, 1.0)],
-            self.progress,
-        ), context.transform, graphics);
+        let pos = if self.progress > 0.0 && self.progress < 1.0 {
+            let sweep = interpolate_path(
+                &[([centre[0] - 30.0, centre[1] + 30.0], 0.0),
+                  ([centre[0] + 30.0, centre[1] + 30.0], 1.0)],
+                self.progress,
+            );
+            [sweep[0], sweep[1] + 15.0 * self.knife]
+        } else {
+            [centre[0], centre[1] + 30.0 * self.knife]
+        };
+        knife(pos, context.transform, graphics);
     }
 }
 
 const ONION_LAYERS: usize = 4;
 const ONION_PIECES: usize = 3;
+const ONION_SPLIT: f64 = 1.5;
+const ONION_SPREAD_SPEED: f64 = 0.4;
+const ONION_SPREAD_LIMIT: f64 = 35.0;
+const ONION_BURNING: f64 = 1.3;
+const ONION_UNEVEN: f64 = 0.4;
+const UNEVEN_PENALTY: f64 = 2.0;
 const RAW_ONION: [f32; 4] = [1.0, 0.95, 0.9, 0.8];
 const COOKED_ONION: [f32; 4] = [214.0 / 255.0, 141.0 / 255.0, 38.0 / 255.0, 0.8];
 
 pub struct Onion {
     pos: [f64; 2],
     heat: f64,
+    temperature: Temperature,
     cooked: [f64; ONION_LAYERS],
-    layers: [[OnionPiece; ONION_PIECES]; ONION_LAYERS],
+    layers: [Vec<OnionPiece>; ONION_LAYERS],
     bounds: Rectangle,
 }
 
@@ -753,32 +1556,50 @@ pub struct OnionPiece {
     start: f64,
     end: f64,
     thickness: f64,
+    size: f64,
 }
 
 impl Onion {
-    pub fn new(pos: [f64; 2]) -> Onion {
-        Onion::with_cooked(pos, 0.0)
+    pub fn with_cooked(pos: [f64; 2], cooked: f64) -> Onion {
+        let mut onion = Onion::chopped(pos, 0.0);
+        onion.cooked = [cooked; ONION_LAYERS];
+        onion
     }
 
-    pub fn with_cooked(pos: [f64; 2], cooked: f64) -> Onion {
-        let layers: [[OnionPiece; ONION_PIECES]; ONION_LAYERS] = array_init::array_init(
-            |_| array_init::array_init(
-                |_| OnionPiece::new()
-            )
+    /// An onion cut into pieces whose sizes vary by up to `unevenness` either way
+    pub fn chopped(pos: [f64; 2], unevenness: f64) -> Onion {
+        let layers: [Vec<OnionPiece>; ONION_LAYERS] = array_init::array_init(
+            |_| (0..ONION_PIECES).map(|_| OnionPiece::new(1.0 + unevenness * (2.0 * rand::random::<f64>() - 1.0))).collect()
         );
-        let bounds = layers.iter()
-            .flatten()
-            .map(|p| p.bounds(pos))
-            .fold(Rectangle::centered(pos, [0.0, 0.0]), |r, s| r.union(&s));
-        Onion{
+        let mut onion = Onion{
             pos,
             heat: 0.0,
-            cooked: [cooked; ONION_LAYERS],
+            temperature: Temperature::new(),
+            cooked: [0.0; ONION_LAYERS],
             layers,
-            bounds,
+            bounds: Rectangle::centered(pos, [0.0, 0.0]),
+        };
+        onion.update_bounds();
+        onion
+    }
+
+    /// How quickly a layer cooks, with thicker pieces taking longer
+    fn cook_rate(&self, layer: usize) -> f64 {
+        let pieces = &self.layers[layer];
+        if pieces.is_empty() {
+            0.0
+        } else {
+            pieces.iter().map(|p| 1.0 / p.size).sum::<f64>() / pieces.len() as f64
         }
     }
 
+    fn update_bounds(&mut self) {
+        self.bounds = self.layers.iter()
+            .flatten()
+            .map(|p| p.bounds(self.pos))
+            .fold(Rectangle::centered(self.pos, [0.0, 0.0]), |r, s| r.union(&s));
+    }
+
     pub fn scramble(&mut self) {
         let mut rng = rand::thread_rng();
         for i in (1..self.layers.len()).rev() {
@@ -787,15 +1608,57 @@ impl Onion {
             self.layers.swap(i, j);
             self.cooked.swap(i, j);
         }
+        let spread = self.portion().sqrt();
         for layer in &mut self.layers {
             for piece in layer {
-                piece.scramble();
+                piece.scramble(spread);
             }
         }
-        self.bounds = self.layers.iter()
-            .flatten()
-            .map(|p| p.bounds(self.pos))
-            .fold(Rectangle::centered(self.pos, [0.0, 0.0]), |r, s| r.union(&s));
+        self.update_bounds();
+    }
+
+    /// Take a single portion out of the pile, from the pieces nearest to `pos`
+    fn split(&mut self, pos: [f64; 2]) -> Onion {
+        let centre = self.pos;
+        let distance = |p: &OnionPiece| {
+            let c = p.centre(centre);
+            (c[0] - pos[0]).powi(2) + (c[1] - pos[1]).powi(2)
+        };
+        let layers = array_init::array_init(|i| {
+            let layer: &mut Vec<OnionPiece> = &mut self.layers[i];
+            layer.sort_by(|a, b| distance(b).partial_cmp(&distance(a)).unwrap());
+            let n = ONION_PIECES.min(layer.len());
+            layer.split_off(layer.len() - n)
+        });
+        self.update_bounds();
+        let mut portion = Onion{
+            pos: self.pos,
+            heat: 0.0,
+            temperature: self.temperature,
+            cooked: self.cooked,
+            layers,
+            bounds: self.bounds,
+        };
+        portion.update_bounds();
+        portion
+    }
+
+    /// Turn the pile over so the least cooked layers end up touching the hotplate
+    fn turn_over(&mut self) {
+        let mut order: Vec<usize> = (0..ONION_LAYERS).collect();
+        order.sort_by(|a, b| self.cooked[*a].partial_cmp(&self.cooked[*b]).unwrap());
+        let cooked = self.cooked;
+        let mut layers: Vec<Vec<OnionPiece>> = self.layers.iter_mut().map(std::mem::take).collect();
+        for (i, j) in order.into_iter().enumerate() {
+            self.cooked[i] = cooked[j];
+            self.layers[i] = std::mem::take(&mut layers[j]);
+        }
+        for layer in &mut self.layers {
+            for piece in layer {
+                piece.nudge();
+            }
+        }
+        self.update_bounds();
     }
 }
 
@@ -804,9 +1667,24 @@ impl Entity for Onion {
         self.bounds
     }
 
+    fn shape(&self) -> Shape {
+        Shape::Union(self.layers.iter()
+                     .flatten()
+                     .map(|p| p.shape(self.pos))
+                     .collect())
+    }
+
+    fn solid(&self) -> bool {
+        true
+    }
+
     fn select(&mut self, pos: [f64; 2]) -> Selection {
-        if self.bounds().intersect_point(pos) {
-            Selection::This
+        if self.shape().contains(pos) {
+            if self.portion() > ONION_SPLIT {
+                Selection::New(Rc::new(RefCell::new(self.split(pos))))
+            } else {
+                Selection::This
+            }
         } else {
             Selection::None
         }
@@ -814,23 +1692,56 @@ impl Entity for Onion {
 
     fn update(&mut self, dt: f64) -> Vec<Rc<RefCell<dyn Entity>>> {
         for i in 0..ONION_LAYERS {
-            self.cooked[i] += dt * self.heat * [1.0, 0.6, 0.3, 0.1][i];
+            self.cooked[i] += dt * self.heat * [1.0, 0.6, 0.3, 0.1][i] * self.cook_rate(i);
+        }
+        if self.heat > 0.0 {
+            // Onions slowly spread out over the hotplate as they soften
+            let limit = ONION_SPREAD_LIMIT * self.portion().sqrt();
+            for layer in &mut self.layers {
+                for piece in layer {
+                    piece.spread(dt * self.heat * ONION_SPREAD_SPEED, limit);
+                }
+            }
+            self.update_bounds();
         }
+        self.temperature.update(dt, self.heat, self.cooked()[0]);
         if rand::random::<f64>() < dt * self.heat * 20.0 {
             let bounds = self.bounds().as_floats();
             vec![Rc::new(RefCell::new(Smoke::new([
                 bounds[0] + rand::random::<f64>() * bounds[2],
                 bounds[1] + rand::random::<f64>() * bounds[3],
-            ], 0.4 * (3.8 - 3.0 * self.cooked[0] as f32).max(0.0).min(1.0))))]
+            ], 0.4 * (3.8 - 3.0 * self.cooked[0] as f32).clamp(0.0, 1.0))))]
         } else {
-            vec![]
+            self.temperature.steam(dt, self.heat, self.cooked()[0], self.bounds())
+        }
+    }
+
+    fn freshness(&self) -> f64 {
+        self.temperature.freshness()
+    }
+
+    fn warning(&self) -> Option<&'static str> {
+        let [mean, max] = self.cooked();
+        if self.heat == 0.0 {
+            None
+        } else if max > ONION_BURNING {
+            Some("hint_burning")
+        } else if max - mean > ONION_UNEVEN {
+            Some("hint_stir")
+        } else {
+            None
         }
     }
 
+    /// How many onions' worth of pieces are in this pile
+    fn portion(&self) -> f64 {
+        self.layers.iter().map(|l| l.len()).sum::<usize>() as f64 / (ONION_LAYERS * ONION_PIECES) as f64
+    }
+
     fn cooked(&self) -> [f64; 2] {
         [
             self.cooked.iter().sum::<f64>() / self.cooked.len() as f64,
-            self.cooked.iter().cloned().fold(0./0., f64::max),
+            self.cooked.iter().cloned().fold(f64::NAN, f64::max),
         ]
     }
 
@@ -856,14 +1767,50 @@ impl Entity for Onion {
         self.heat = heat;
     }
 
+    fn use_tool(&mut self, tool: Tool, pos: [f64; 2]) -> bool {
+        if tool == Tool::Spatula && self.heat > 0.0 && self.bounds().intersect_point(pos) {
+            self.turn_over();
+            true
+        } else {
+            false
+        }
+    }
+
     fn topping(&self) -> Option<Topping> {
         Some(Topping::Onion)
     }
 
+    fn add_topping(&mut self, topping: &Rc<RefCell<dyn Entity>>) -> Selection {
+        // Onions dropped onto another pile on the hotplate join it
+        if self.heat == 0.0 {
+            return Selection::None;
+        }
+        let mut topping = topping.borrow_mut();
+        if let Some(other) = (*topping).as_any_mut().downcast_mut::<Onion>() {
+            let offset = [other.pos[0] - self.pos[0], other.pos[1] - self.pos[1]];
+            let total = self.portion() + other.portion();
+            for i in 0..ONION_LAYERS {
+                let (n, m) = (self.layers[i].len() as f64, other.layers[i].len() as f64);
+                if n + m > 0.0 {
+                    self.cooked[i] = (self.cooked[i] * n + other.cooked[i] * m) / (n + m);
+                }
+                for mut piece in other.layers[i].drain(..) {
+                    piece.rect[0] += offset[0];
+                    piece.rect[1] += offset[1];
+                    self.layers[i].push(piece);
+                }
+            }
+            self.temperature.warmth = (self.temperature.warmth * (total - other.portion()) + other.temperature.warmth * other.portion()) / total;
+            self.update_bounds();
+            Selection::This
+        } else {
+            Selection::None
+        }
+    }
+
     fn add_to(&mut self, pos: [f64; 2], others: &[Rc<RefCell<dyn Entity>>]) -> Selection {
         if others.iter()
-                 .filter(|e| e.borrow().topping() == Some(Topping::Onion))
-                 .next()
+                 .find(|e| e.borrow().topping() == Some(Topping::Onion))
                  .is_none() {
             self.set_pos(pos);
             Selection::This
@@ -874,7 +1821,7 @@ impl Entity for Onion {
 
     fn draw(&self, context: Context, graphics: &mut G) {
         for (layer, cooked) in self.layers.iter().zip(&self.cooked) {
-            let colour = interpolate_colour(&[(RAW_ONION, 0.0), (COOKED_ONION, 1.0), (BLACK, 1.4)], *cooked as f32);
+            let colour = self.temperature.colour(interpolate_colour(&[(RAW_ONION, 0.0), (COOKED_ONION, 1.0), (BLACK, 1.4)], *cooked as f32));
             for piece in layer {
                 piece.draw(self.pos, colour, context, graphics);
             }
@@ -883,7 +1830,7 @@ impl Entity for Onion {
 }
 
 impl OnionPiece {
-    fn new() -> OnionPiece {
+    fn new(size: f64) -> OnionPiece {
         let start = rand::random::<f64>() * std::f64::consts::PI * 2.0;
         let end = start + (0.4 + 0.6 * rand::random::<f64>()) * std::f64::consts::PI;
         let r = 5.0 + 15.0 * rand::random::<f64>();
@@ -893,17 +1840,41 @@ impl OnionPiece {
             rect: [x, y, 2.0 * r, 2.0* r],
             start,
             end,
-            thickness: 2.0 + rand::random::<f64>() * 2.0,
+            thickness: (2.0 + rand::random::<f64>() * 2.0) * size,
+            size,
         }
     }
 
-    fn scramble(&mut self) {
+    fn scramble(&mut self, spread: f64) {
         let len = self.end - self.start;
         self.start = rand::random::<f64>() * std::f64::consts::PI * 2.0;
         self.end = self.start + len;
         let r = self.rect[2] / 2.0;
-        self.rect[0] = 40.0 * rand::random::<f64>() - 20.0 - (1.0 + (self.end.sin() - self.start.sin()) / (self.end - self.start)) * r;
-        self.rect[1] = 40.0 * rand::random::<f64>() - 20.0 - (1.0 + (self.start.cos() - self.end.cos()) / (self.end - self.start)) * r;
+        self.rect[0] = spread * (40.0 * rand::random::<f64>() - 20.0) - (1.0 + (self.end.sin() - self.start.sin()) / (self.end - self.start)) * r;
+        self.rect[1] = spread * (40.0 * rand::random::<f64>() - 20.0) - (1.0 + (self.start.cos() - self.end.cos()) / (self.end - self.start)) * r;
+    }
+
+    fn centre(&self, pos: [f64; 2]) -> [f64; 2] {
+        [pos[0] + self.rect[0] + self.rect[2] / 2.0, pos[1] + self.rect[1] + self.rect[3] / 2.0]
+    }
+
+    /// Turn the piece over in place
+    fn nudge(&mut self) {
+        let turn = (rand::random::<f64>() - 0.5) * std::f64::consts::PI;
+        self.start += turn;
+        self.end += turn;
+        self.rect[0] += 4.0 * rand::random::<f64>() - 2.0;
+        self.rect[1] += 4.0 * rand::random::<f64>() - 2.0;
+    }
+
+    /// Move the piece away from the middle of the pile by a fraction of its distance, up to `limit`
+    fn spread(&mut self, amount: f64, limit: f64) {
+        let c = self.centre([0.0, 0.0]);
+        let d = (c[0] * c[0] + c[1] * c[1]).sqrt();
+        if d < limit {
+            self.rect[0] += c[0] * amount;
+            self.rect[1] += c[1] * amount;
+        }
     }
 
     fn bounds(&self, pos: [f64; 2]) -> Rectangle {
@@ -935,6 +1906,17 @@ impl OnionPiece {
         Rectangle::new([pos[0] + self.rect[0] + self.rect[2] / 2.0 + xmin, pos[1] + self.rect[1] + self.rect[3] / 2.0 + ymin], [xmax - xmin, ymax - ymin])
     }
 
+    fn shape(&self, pos: [f64; 2]) -> Shape {
+        let r = self.rect[2] / 2.0;
+        Shape::Arc{
+            centre: [pos[0] + self.rect[0] + r, pos[1] + self.rect[1] + r],
+            radius: r,
+            start: self.start,
+            end: self.end,
+            thickness: self.thickness + PICK_TOLERANCE,
+        }
+    }
+
     fn draw(&self, pos: [f64; 2], colour: [f32; 4], context: Context, graphics: &mut G) {
         piston_window::circle_arc(
             colour,
@@ -962,32 +1944,54 @@ pub struct Blob {
 
 impl Squirt {
     pub fn new(condiment: Condiment, pos: [f64; 2]) -> Squirt {
-        let scale = BREAD_SIZE[0] / 2.0;
-        let mut rng = rand::thread_rng();
-        let n_blob = rng.gen_range(4, 6);
-        let mut blobs = Vec::with_capacity(n_blob);
-        let offset = match condiment {
-            Condiment::Sauce => [-0.2, -0.2],
-            Condiment::Mustard => [0.2, 0.2],
-        };
-        blobs.push(Blob{offset: [offset[0] * scale, offset[1] * scale], radius: 0.2 * scale});
-        for _ in 1..n_blob {
-            blobs.push(Blob{
-                offset: [(offset[0] - 0.2 + 0.4 * rng.gen::<f64>()) * scale,
-                         (offset[1] - 0.2 + 0.4 * rng.gen::<f64>()) * scale],
-                radius: (0.2 + 0.2 * rng.gen::<f64>()) * scale,
-            });
-        }
-        let mut it = blobs.iter().map(|p| p.bounds(pos));
-        let first = it.next();
-        let bounds = it.fold(first.unwrap(), |r, s| r.union(&s));
         Squirt{
             pos,
-            blobs,
-            bounds,
+            blobs: vec![],
+            bounds: Rectangle::centered(pos, [0.0, 0.0]),
             condiment,
         }
     }
+
+    /// A squirt zigzagging across the bread, with `amount` servings of the condiment
+    pub fn with_amount(condiment: Condiment, pos: [f64; 2], amount: f64) -> Squirt {
+        let scale = BREAD_SIZE[0] / 2.0;
+        let mut rng = rand::thread_rng();
+        let n_blob = (amount * SQUIRT_BLOBS).round() as usize;
+        let mut squirt = Squirt::new(condiment, pos);
+        if condiment.dispenser() == Dispenser::Tub {
+            // Garnishes are scattered all over
+            for _ in 0..n_blob {
+                squirt.add([pos[0] + (rng.gen::<f64>() - 0.5) * 1.4 * scale,
+                            pos[1] + (rng.gen::<f64>() - 0.5) * 1.4 * scale]);
+            }
+            return squirt;
+        }
+        let offset = condiment.info().placement;
+        let per_row = (1.2 * scale / SQUIRT_SPACING).floor();
+        let rows = (n_blob as f64 / per_row).ceil();
+        for i in 0..n_blob {
+            let row = (i as f64 / per_row).floor();
+            let along = (i as f64 % per_row) / per_row;
+            let along = if row as usize % 2 == 0 { along } else { 1.0 - along };
+            let y = offset * scale + (row - (rows - 1.0) / 2.0) * scale / SQUIRT_ROWS;
+            squirt.add([pos[0] + (along - 0.5) * 1.2 * scale + rng.gen::<f64>() - 0.5,
+                        pos[1] + y + rng.gen::<f64>() - 0.5]);
+        }
+        squirt
+    }
+
+    /// Squeeze a little more of the condiment out at `pos`, pooling if the nozzle hasn't moved
+    fn add(&mut self, pos: [f64; 2]) {
+        let offset = [pos[0] - self.pos[0], pos[1] - self.pos[1]];
+        match self.blobs.last_mut() {
+            Some(last) if (last.offset[0] - offset[0]).hypot(last.offset[1] - offset[1]) < 1.0 => {
+                last.radius = last.radius.hypot(SQUIRT_RADIUS);
+            },
+            _ => self.blobs.push(Blob{offset, radius: SQUIRT_RADIUS}),
+        }
+        let blob = self.blobs.last().unwrap().bounds(self.pos);
+        self.bounds = if self.blobs.len() == 1 { blob } else { self.bounds.union(&blob) };
+    }
 }
 
 impl Entity for Squirt {
@@ -995,8 +1999,14 @@ impl Entity for Squirt {
         self.bounds
     }
 
+    fn shape(&self) -> Shape {
+        Shape::Union(self.blobs.iter()
+                     .map(|b| Shape::Circle([self.pos[0] + b.offset[0], self.pos[1] + b.offset[1]], b.radius))
+                     .collect())
+    }
+
     fn select(&mut self, pos: [f64; 2]) -> Selection {
-        if self.bounds().intersect_point(pos) {
+        if self.shape().contains(pos) {
             Selection::This
         } else {
             Selection::None
@@ -1016,14 +2026,18 @@ impl Entity for Squirt {
         self.drag(self.pos,pos);
     }
 
+    /// How many servings of the condiment have been squeezed out
+    fn portion(&self) -> f64 {
+        self.blobs.iter().map(|b| (b.radius / SQUIRT_RADIUS).powi(2)).sum::<f64>() / SQUIRT_BLOBS
+    }
+
     fn topping(&self) -> Option<Topping> {
         Some(Topping::Condiment(self.condiment))
     }
 
     fn add_to(&mut self, pos: [f64; 2], others: &[Rc<RefCell<dyn Entity>>]) -> Selection {
         if others.iter()
-                 .filter(|e| e.borrow().topping() == Some(Topping::Condiment(self.condiment)))
-                 .next()
+                 .find(|e| e.borrow().topping() == Some(Topping::Condiment(self.condiment)))
                  .is_none() {
             self.set_pos(pos);
             Selection::This
@@ -1034,10 +2048,23 @@ impl Entity for Squirt {
 
     fn draw(&self, context: Context, graphics: &mut G) {
         for blob in &self.blobs {
-            piston_window::ellipse(self.condiment.colour(),
-                                   blob.bounds(self.pos).as_floats(),
-                                   context.transform,
-                                   graphics);
+            match self.condiment.dispenser() {
+                Dispenser::Bottle => piston_window::ellipse(self.condiment.colour(),
+                                                            blob.bounds(self.pos).as_floats(),
+                                                            context.transform,
+                                                            graphics),
+                Dispenser::Tub => {
+                    // Shreds lie at a jumble of angles that stays put from frame to frame
+                    let angle = (blob.offset[0] * 12.9898 + blob.offset[1] * 78.233).sin() * std::f64::consts::PI;
+                    let centre = [self.pos[0] + blob.offset[0], self.pos[1] + blob.offset[1]];
+                    let (dx, dy) = (blob.radius * angle.cos(), blob.radius * angle.sin());
+                    piston_window::line(self.condiment.colour(),
+                                        blob.radius / 3.0,
+                                        [centre[0] - dx, centre[1] - dy, centre[0] + dx, centre[1] + dy],
+                                        context.transform,
+                                        graphics);
+                },
+            }
         }
     }
 }
@@ -1051,11 +2078,31 @@ impl Blob {
 pub struct Bottle {
     pos: [f64; 2],
     condiment: Condiment,
+    held: bool,
+    squeezing: bool,
+    flow: f64,
+    squeezed: Vec<[f64; 2]>,
 }
 
 impl Bottle {
     pub fn new(condiment: Condiment, pos: [f64; 2]) -> Bottle {
-        Bottle{pos, condiment}
+        Bottle{
+            pos,
+            condiment,
+            held: false,
+            squeezing: false,
+            flow: 0.0,
+            squeezed: vec![],
+        }
+    }
+
+    /// The bottle is held upside down, so the nozzle points at whatever is underneath it
+    fn direction(&self) -> f64 {
+        if self.held { -1.0 } else { 1.0 }
+    }
+
+    fn nozzle(&self) -> [f64; 2] {
+        [self.pos[0], self.pos[1] - 40.0 * self.direction()]
     }
 }
 
@@ -1064,55 +2111,130 @@ impl Entity for Bottle {
         Rectangle::centered(self.pos, [20.0, 80.0])
     }
 
+    fn shape(&self) -> Shape {
+        let d = self.direction();
+        Shape::Union(vec![
+            Shape::Rectangle(Rectangle::centered([self.pos[0], self.pos[1] + 7.5 * d], [20.0, 65.0])),
+            Shape::Polygon(vec![[self.pos[0] - 8.0, self.pos[1] - 25.0 * d],
+                                [self.pos[0] - 1.0 - PICK_TOLERANCE, self.pos[1] - 40.0 * d],
+                                [self.pos[0] + 1.0 + PICK_TOLERANCE, self.pos[1] - 40.0 * d],
+                                [self.pos[0] + 8.0, self.pos[1] - 25.0 * d]]),
+        ])
+    }
+
     fn select(&mut self, pos: [f64; 2]) -> Selection {
-        if self.bounds().intersect_point(pos) {
+        if self.shape().contains(pos) {
             Selection::This
         } else {
             Selection::None
         }
     }
 
+    fn update_selected(&mut self, dt: f64) {
+        if self.squeezing {
+            self.flow += dt * SQUEEZE_RATE;
+            while self.flow >= 1.0 {
+                self.flow -= 1.0;
+                self.squeezed.push(self.nozzle());
+            }
+        }
+    }
+
+    fn grab(&mut self) {
+        self.held = true;
+    }
+
+    fn drop(&mut self) {
+        self.held = false;
+        self.squeezing = false;
+        self.flow = 0.0;
+    }
+
     fn drag(&mut self, from: [f64; 2], to: [f64; 2]) {
         for i in 0..2 {
             self.pos[i] += to[i] - from[i];
         }
     }
 
+    fn squeeze(&mut self, squeezing: bool) {
+        self.squeezing = squeezing;
+    }
+
+    fn take_tool_use(&mut self) -> Option<(Tool, Vec<[f64; 2]>)> {
+        if self.squeezed.is_empty() {
+            None
+        } else {
+            Some((Tool::Bottle(self.condiment), std::mem::take(&mut self.squeezed)))
+        }
+    }
+
     fn set_pos(&mut self, pos: [f64; 2]) {
         self.pos = pos;
     }
 
     fn draw(&self, context: Context, graphics: &mut G) {
+        let d = self.direction();
         piston_window::rectangle(self.condiment.colour(),
-                                 [self.pos[0] - 10.0, self.pos[1] - 25.0, 20.0, 65.0],
+                                 Rectangle::centered([self.pos[0], self.pos[1] + 7.5 * d], [20.0, 65.0]).as_floats(),
                                  context.transform,
                                  graphics);
         piston_window::polygon(self.condiment.colour(),
-                               &[[self.pos[0] - 8.0, self.pos[1] - 25.0],
-                                 [self.pos[0] - 1.0, self.pos[1] - 40.0],
-                                 [self.pos[0] + 1.0, self.pos[1] - 40.0],
-                                 [self.pos[0] + 8.0, self.pos[1] - 25.0]],
+                               &[[self.pos[0] - 8.0, self.pos[1] - 25.0 * d],
+                                 [self.pos[0] - 1.0, self.pos[1] - 40.0 * d],
+                                 [self.pos[0] + 1.0, self.pos[1] - 40.0 * d],
+                                 [self.pos[0] + 8.0, self.pos[1] - 25.0 * d]],
                                context.transform,
                                graphics);
+        if self.squeezing {
+            let nozzle = self.nozzle();
+            piston_window::rectangle(self.condiment.colour(),
+                                     [nozzle[0] - 1.0, nozzle[1], 2.0, 6.0],
+                                     context.transform,
+                                     graphics);
+        }
     }
+}
 
-    fn topping(&self) -> Option<Topping> {
-        Some(Topping::Condiment(self.condiment))
+pub struct Tub {
+    pos: [f64; 2],
+    condiment: Condiment,
+}
+
+impl Tub {
+    pub fn new(condiment: Condiment, pos: [f64; 2]) -> Tub {
+        Tub{pos, condiment}
     }
+}
 
-    fn add_to(&mut self, pos: [f64; 2], others: &[Rc<RefCell<dyn Entity>>]) -> Selection {
-        if others.iter()
-                 .filter(|e| e.borrow().topping() == Some(Topping::Condiment(self.condiment)))
-                 .next()
-                 .is_none() {
-            Selection::New(Rc::new(RefCell::new(Squirt::new(self.condiment, pos))))
+impl Entity for Tub {
+    fn bounds(&self) -> Rectangle {
+        Rectangle::centered(self.pos, TUB_SIZE)
+    }
+
+    /// Take out a pinch of the garnish to sprinkle over the bread
+    fn select(&mut self, pos: [f64; 2]) -> Selection {
+        if self.shape().contains(pos) {
+            Selection::New(Rc::new(RefCell::new(Squirt::with_amount(self.condiment, pos, 1.0))))
         } else {
             Selection::None
         }
     }
+
+    fn draw(&self, context: Context, graphics: &mut G) {
+        let bounds = self.bounds().as_floats();
+        rounded_rectangle(LIGHT_GREY,
+                          bounds,
+                          3.0,
+                          context.transform,
+                          graphics);
+        piston_window::rectangle(self.condiment.colour(),
+                                 [bounds[0] + 3.0, bounds[1] + 3.0, bounds[2] - 6.0, bounds[3] - 6.0],
+                                 context.transform,
+                                 graphics);
+    }
 }
 
-#[derive(Clone, Copy)]
+#[derive(PartialEq, Eq, Clone, Copy)]
 pub enum Mood {
     Happy,
     Neutral,
@@ -1120,510 +2242,1001 @@ pub enum Mood {
     Sick,
 }
 
-pub struct Customer {
-    pos: [f64; 2],
-    order: Bread,
-    meal: Option<Bread>,
-    mood: Option<Mood>,
-}
+impl Mood {
+    /// How pleased the customer is, from 0 for sick up to 3 for happy
+    fn rank(&self) -> u32 {
+        match self {
+            Mood::Sick => 0,
+            Mood::Sad => 1,
+            Mood::Neutral => 2,
+            Mood::Happy => 3,
+        }
+    }
 
-impl Customer {
-    pub fn new(pos: [f64; 2]) -> Customer {
-        let mut order = Bread{
-            pos: [pos[0] + ORDER_OFFSET[0], pos[1] + ORDER_OFFSET[1]],
-            toppings: Vec::with_capacity(5),
-        };
-        let mut rng = rand::thread_rng();
-        
-        // Filling cooked between 0.8 and 1.2 with peak at 1.0
-        let filling_cooked: f64 = 0.8 + 0.4 * rng.sample(Beta::new(2.0, 2.0).unwrap());
-        // Onion cooked between 0.8 and 1.2 with peak at 1.0
-        let onion_cooked: f64 = 0.8 + 0.4 * rng.sample(Beta::new(2.0, 2.0).unwrap());
-        
-        let filling: f64 = rng.gen();
-        if filling < 0.5 {
-            // 50% chance of one sausage
-            order.add_topping(&(Rc::new(RefCell::new(Cookable::with_cooked(
-                Filling::Sausage,
-                pos,
-                filling_cooked,
-            ))) as Rc<RefCell<dyn Entity>>));
-        } else if filling < 0.75 {
-            // 25% chance of two sausages
-            let sausage_1: Rc<RefCell<dyn Entity>> = Rc::new(RefCell::new(Cookable::with_cooked(
-                Filling::Sausage,
-                pos,
-                filling_cooked,
-            )));
-            order.add_topping(&sausage_1);
-            let sausage_2: Rc<RefCell<dyn Entity>> = Rc::new(RefCell::new(Cookable::with_cooked(
-                Filling::Sausage,
-                pos,
-                filling_cooked,
-            )));
-            order.add_topping(&sausage_2);
-        } else {
-            // 25% chance of patty
-            order.add_topping(&(Rc::new(RefCell::new(Cookable::with_cooked(
-                Filling::VeggiePatty,
-                pos,
-                filling_cooked,
-            ))) as Rc<RefCell<dyn Entity>>));
+    fn from_rank(rank: u32) -> Mood {
+        match rank {
+            0 => Mood::Sick,
+            1 => Mood::Sad,
+            2 => Mood::Neutral,
+            _ => Mood::Happy,
         }
+    }
 
-        // 40% chance the customer wants onion
-        if rng.sample(Bernoulli::new(0.4).unwrap()) {
-            order.add_topping(&(Rc::new(RefCell::new(Onion::with_cooked(
-                pos,
-                onion_cooked,
-            ))) as Rc<RefCell<dyn Entity>>));
+    fn colour(&self, palette: Palette) -> [f32; 4] {
+        let [happy, neutral, sad] = palette.moods();
+        match self {
+            Mood::Happy => happy,
+            Mood::Neutral => neutral,
+            Mood::Sad | Mood::Sick => sad,
         }
+    }
+}
 
-        let condiment: f64 = rng.gen();
-        if condiment < 0.5 {
-            // 50% chance of tomato sauce
-            order.add_topping(&(Rc::new(RefCell::new(Squirt::new(
-                Condiment::Sauce,
-                pos,
-            ))) as Rc<RefCell<dyn Entity>>));
-        } else if condiment < 0.7 {
-            // 20% chance of mustard
-            order.add_topping(&(Rc::new(RefCell::new(Squirt::new(
-                Condiment::Mustard,
-                pos,
-            ))) as Rc<RefCell<dyn Entity>>));
-        } else if condiment < 0.9 {
-            // 20% chance of tomato sauce and mustard
-            order.add_topping(&(Rc::new(RefCell::new(Squirt::new(
-                Condiment::Sauce,
-                pos,
-            ))) as Rc<RefCell<dyn Entity>>));
-            order.add_topping(&(Rc::new(RefCell::new(Squirt::new(
-                Condiment::Mustard,
-                pos,
-            ))) as Rc<RefCell<dyn Entity>>));
-        } else {
-            // 10% chance of no condiment
+/// How well cooked a customer wants their filling or onions
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum Doneness {
+    Rare,
+    Medium,
+    WellDone,
+    ExtraCrispy,
+}
+
+impl Doneness {
+    fn target(&self) -> f64 {
+        match self {
+            Doneness::Rare => 0.85,
+            Doneness::Medium => 1.0,
+            Doneness::WellDone => 1.15,
+            Doneness::ExtraCrispy => 1.3,
         }
+    }
 
-        Customer{
-            pos,
-            order,
-            meal: None,
-            mood: None,
+    /// Number of flames shown on the order
+    fn flames(&self) -> usize {
+        match self {
+            Doneness::Rare => 1,
+            Doneness::Medium => 2,
+            Doneness::WellDone => 3,
+            Doneness::ExtraCrispy => 4,
         }
     }
-}
 
-impl Entity for Customer {
-    fn bounds(&self) -> Rectangle {
-        Rectangle::centered(self.pos, PLATE_SIZE)
+    /// Pick a doneness from `options`, weighted by how often customers ask for each
+    fn choose<R: Rng>(options: &[(Doneness, f64)], rng: &mut R) -> Doneness {
+        let weights = WeightedIndex::new(options.iter().map(|o| o.1)).unwrap();
+        options[rng.sample(weights)].0
     }
 
-    fn set_pos(&mut self, pos: [f64; 2]) {
-        self.pos = pos;
-        self.order.set_pos([pos[0] + ORDER_OFFSET[0], pos[1] + ORDER_OFFSET[1]]);
-        if let Some(meal) = &mut self.meal {
-            meal.set_pos(self.pos);
+    fn draw(&self, colour: [f32; 4], pos: [f64; 2], context: Context, graphics: &mut G) {
+        let n = self.flames();
+        for i in 0..n {
+            let x = pos[0] + (i as f64 - (n - 1) as f64 / 2.0) * FLAME_SPACING;
+            piston_window::polygon(colour,
+                                   &[[x, pos[1] - 5.0],
+                                     [x + 3.0, pos[1] + 1.0],
+                                     [x + 2.0, pos[1] + 4.0],
+                                     [x - 2.0, pos[1] + 4.0],
+                                     [x - 3.0, pos[1] + 1.0]],
+                                   context.transform,
+                                   graphics);
         }
     }
+}
 
-    fn draw(&self, context: Context, graphics: &mut G) {
-        piston_window::ellipse(WHITE,
-                               self.bounds().as_floats(),
-                               context.transform,
-                               graphics);
-        piston_window::ellipse(LIGHT_GREY,
-                               Rectangle::centered(self.pos, [PLATE_SIZE[0] * 0.65, PLATE_SIZE[1] * 0.65]).as_floats(),
-                               context.transform,
-                               graphics);
+/// The fillings people order, and how likely each is
+const ORDERS: [(&[Filling], f64); 6] = [
+    (&[Filling::Sausage], 0.35),
+    (&[Filling::Sausage, Filling::Sausage], 0.2),
+    (&[Filling::VeggiePatty], 0.15),
+    (&[Filling::Halloumi], 0.1),
+    (&[Filling::Bacon, Filling::Bacon, Filling::Egg], 0.1),
+    (&[Filling::Sausage, Filling::Egg], 0.1),
+];
+
+/// What customers in a queue can order
+#[derive(Deserialize)]
+pub struct Menu {
+    fillings: Vec<Filling>,
+    condiments: Vec<Condiment>,
+    onion: bool,
+    toast: bool,
+}
+
+impl Menu {
+    /// The fillings a customer can order together from the menu, with how often each is ordered
+    fn orders(&self) -> Vec<&'static (&'static [Filling], f64)> {
+        ORDERS.iter().filter(|(f, _)| f.iter().all(|f| self.fillings.contains(f))).collect()
+    }
+
+    /// Whether there's anything on the menu a customer can order
+    pub fn can_order(&self) -> bool {
+        !self.orders().is_empty()
+    }
+}
+
+/// A particular order, such as one set by the tutorial
+#[derive(Deserialize)]
+pub struct OrderSpec {
+    fillings: Vec<Filling>,
+    #[serde(default)]
+    onion: bool,
+    #[serde(default)]
+    condiments: Vec<Condiment>,
+    #[serde(default)]
+    toasted: bool,
+}
 
-        let thought_colour = match self.mood {
-            None => WHITE,
-            Some(Mood::Happy) => HAPPY,
-            Some(Mood::Neutral) => NEUTRAL,
-            Some(Mood::Sad) => SAD,
-            Some(Mood::Sick) => SAD,
+/// One bread a customer wants, and how it went down once delivered
+struct Order {
+    bread: Bread,
+    doneness: Option<Doneness>,
+    onion_doneness: Option<Doneness>,
+    mood: Option<Mood>,
+}
+
+pub struct Customer {
+    pos: [f64; 2],
+    orders: Vec<Order>,
+    meals: Vec<Bread>,
+    mood: Option<Mood>,
+    /// How long the customer has been queueing
+    waited: f64,
+    /// How far along the queue path the customer is, or along their way out once served
+    distance: f64,
+    exit: Option<Vec<([f64; 2], f64)>>,
+    palette: Palette,
+}
+
+impl Order {
+    /// Exactly what `spec` asks for, with everything cooked just right
+    fn exact(pos: [f64; 2], spec: &OrderSpec) -> Order {
+        let mut order = Bread{
+            toppings: Vec::with_capacity(spec.fillings.len() + spec.condiments.len() + 1),
+            ..Bread::with_toasted([pos[0] + ORDER_OFFSET[0], pos[1] + ORDER_OFFSET[1]], if spec.toasted { 1.0 } else { 0.0 })
         };
+        for filling in &spec.fillings {
+            order.add_topping(&(Rc::new(RefCell::new(Cookable::with_cooked(*filling, pos, 1.0))) as Rc<RefCell<dyn Entity>>));
+        }
+        if spec.onion {
+            order.add_topping(&(Rc::new(RefCell::new(Onion::with_cooked(pos, 1.0))) as Rc<RefCell<dyn Entity>>));
+        }
+        for condiment in &spec.condiments {
+            order.add_topping(&(Rc::new(RefCell::new(Squirt::with_amount(*condiment, pos, 1.0))) as Rc<RefCell<dyn Entity>>));
+        }
+        Order{
+            bread: order,
+            doneness: None,
+            onion_doneness: None,
+            mood: None,
+        }
+    }
 
-        piston_window::ellipse(thought_colour,
-                               [self.pos[0] + ORDER_OFFSET[0] - 54.0, self.pos[1] + ORDER_OFFSET[1] - 30.0, 60.0, 50.0],
-                               context.transform,
-                               graphics);
-        piston_window::ellipse(thought_colour,
-                               [self.pos[0] + ORDER_OFFSET[0] - 44.0, self.pos[1] + ORDER_OFFSET[1] - 10.0, 55.0, 55.0],
-                               context.transform,
-                               graphics);
-        piston_window::ellipse(thought_colour,
-                               [self.pos[0] + ORDER_OFFSET[0] - 34.0, self.pos[1] + ORDER_OFFSET[1] - 45.0, 70.0, 52.0],
-                               context.transform,
-                               graphics);
-        piston_window::ellipse(thought_colour,
-                               [self.pos[0] + ORDER_OFFSET[0] - 4.0, self.pos[1] + ORDER_OFFSET[1] - 40.0, 50.0, 45.0],
-                               context.transform,
-                               graphics);
-        piston_window::ellipse(thought_colour,
-                               [self.pos[0] + ORDER_OFFSET[0] - 14.0, self.pos[1] + ORDER_OFFSET[1] - 15.0, 60.0, 55.0],
-                               context.transform,
-                               graphics);
+    fn new(pos: [f64; 2], menu: &Menu) -> Order {
+        let mut rng = rand::thread_rng();
 
-        piston_window::ellipse(thought_colour,
-                               [self.pos[0] + ORDER_OFFSET[0] - 50.0, self.pos[1] + ORDER_OFFSET[1] - 53.0, 14.0, 14.0],
-                               context.transform,
-                               graphics);
-        piston_window::ellipse(thought_colour,
-                               [self.pos[0] + ORDER_OFFSET[0] - 65.0, self.pos[1] + ORDER_OFFSET[1] - 74.0, 12.0, 12.0],
-                               context.transform,
-                               graphics);
-        piston_window::ellipse(thought_colour,
-                               [self.pos[0] + ORDER_OFFSET[0] - 67.0, self.pos[1] + ORDER_OFFSET[1] - 100.0, 10.0, 11.0],
-                               context.transform,
-                               graphics);
-        piston_window::ellipse(thought_colour,
-                               [self.pos[0] + ORDER_OFFSET[0] - 60.0, self.pos[1] + ORDER_OFFSET[1] - 126.0, 9.0, 10.0],
-                               context.transform,
-                               graphics);
+        // 30% chance the customer wants their bread toasted
+        let toasted = if menu.toast && rng.sample(Bernoulli::new(0.3).unwrap()) { 1.0 } else { 0.0 };
+        let mut order = Bread{
+            toppings: Vec::with_capacity(5),
+            ..Bread::with_toasted([pos[0] + ORDER_OFFSET[0], pos[1] + ORDER_OFFSET[1]], toasted)
+        };
+        
+        let orders = menu.orders();
+        let weights = WeightedIndex::new(orders.iter().map(|o| o.1)).expect("Nothing to order on the menu");
+        let fillings = orders[rng.sample(weights)].0;
+        // Most customers like their filling medium, but only if every filling can be cooked that way safely
+        let doneness = Doneness::choose(
+            &[(Doneness::Rare, 1.0), (Doneness::Medium, 3.0), (Doneness::WellDone, 1.5)].iter()
+                .cloned()
+                .filter(|(d, _)| fillings.iter().all(|f|
+                    d.target() < f.burnt() - 0.1 && f.undercooked().is_none_or(|min| d.target() > min + 0.1)))
+                .collect::<Vec<_>>(),
+            &mut rng,
+        );
+        for filling in fillings {
+            order.add_topping(&(Rc::new(RefCell::new(Cookable::with_cooked(
+                *filling,
+                pos,
+                doneness.target(),
+            ))) as Rc<RefCell<dyn Entity>>));
+        }
 
-        self.order.draw(context, graphics);
-        if let Some(meal) = &self.meal {
-            meal.draw(context, graphics);
+        // 40% chance the customer wants onion
+        let onion = menu.onion && rng.sample(Bernoulli::new(0.4).unwrap());
+        // 30% of those want their sauce under the onion rather than on top
+        let sauce_under = onion && rng.sample(Bernoulli::new(0.3).unwrap());
+        let onion_doneness = Doneness::choose(
+            &[(Doneness::Medium, 2.0), (Doneness::WellDone, 1.5), (Doneness::ExtraCrispy, 1.0)],
+            &mut rng,
+        );
+        let onion_topping: Rc<RefCell<dyn Entity>> = Rc::new(RefCell::new(Onion::with_cooked(
+            pos,
+            onion_doneness.target(),
+        )));
+        if onion && !sauce_under {
+            order.add_topping(&onion_topping);
+        }
+
+        let mut condiments = Vec::with_capacity(2);
+        let condiment: f64 = rng.gen();
+        if condiment < 0.9 {
+            // 90% chance of a sauce, or 10% chance of none
+            condiments.extend(Condiment::choose(Dispenser::Bottle, menu, &[], &mut rng));
+        }
+        if (0.7..0.9).contains(&condiment) {
+            // 20% chance of a second, different sauce
+            condiments.extend(Condiment::choose(Dispenser::Bottle, menu, &condiments, &mut rng));
+        }
+        for condiment in &condiments {
+            let amount: f64 = rng.gen();
+            let amount = if amount < 0.2 {
+                // 20% chance of just a little
+                0.5
+            } else if amount < 0.8 {
+                1.0
+            } else {
+                // 20% chance of lots
+                2.0
+            };
+            order.add_topping(&(Rc::new(RefCell::new(Squirt::with_amount(
+                *condiment,
+                pos,
+                amount,
+            ))) as Rc<RefCell<dyn Entity>>));
+        }
+
+        if sauce_under {
+            order.add_topping(&onion_topping);
+        }
+
+        // 25% chance of a garnish on top of everything else
+        if rng.sample(Bernoulli::new(0.25).unwrap()) {
+            if let Some(garnish) = Condiment::choose(Dispenser::Tub, menu, &[], &mut rng) {
+                order.add_topping(&(Rc::new(RefCell::new(Squirt::with_amount(
+                    garnish,
+                    pos,
+                    1.0,
+                ))) as Rc<RefCell<dyn Entity>>));
+            }
+        }
+
+        Order{
+            bread: order,
+            doneness: Some(doneness),
+            onion_doneness: if onion { Some(onion_doneness) } else { None },
+            mood: None,
         }
     }
 
-    fn deliver_order(&mut self, order: &Bread) -> Option<Mood> {
-        if self.mood.is_none() && order.bounds().intersect_rect(&self.bounds()) {
-            let mut toppings = order.toppings.clone();
-            let mut score: f64 = 0.0;
-            let mut has_filling = false;
-            let mut sick = false;
-            let mut missing: u32 = 0;
-            let mut wrong: u32 = 0;
-            let mut burnt: u32 = 0;
-            for topping in &self.order.toppings {
-                if let Some(i) = toppings.iter().position(|other|
-                    match (topping.borrow().topping(), other.borrow().topping()) {
-                        (Some(Topping::Filling(_)), Some(Topping::Filling(_))) => true,
-                        (Some(Topping::Onion), Some(Topping::Onion)) => true,
-                        (Some(Topping::Condiment(c1)), Some(Topping::Condiment(c2))) => c1 == c2,
-                        _ => false,
-                    }
-                ) {
-                    let other = toppings.remove(i);
-                    match topping.borrow().topping() {
-                        Some(Topping::Filling(filling)) => {
-                            if let Some(Topping::Filling(other_filling)) = other.borrow().topping() {
-                                if filling == other_filling {
-                                    has_filling = true;
-                                    if filling == Filling::Sausage {
-                                        if other.borrow().cooked()[0] < 0.7 {
-                                            sick = true;
-                                        }
-                                    }
-                                    if other.borrow().cooked()[1] > 1.4 {
-                                        burnt += 1
-                                    }
-                                    if other.borrow().cooked()[0] > 1.4 {
-                                        burnt += 1
-                                    }
-                                    score += 1.0 - (other.borrow().cooked()[0] - topping.borrow().cooked()[0]).powi(2).min(0.1) * 10.0
-                                                 - (other.borrow().cooked()[1] - topping.borrow().cooked()[1]).powi(2).min(0.1) * 10.0;
-                                } else {
-                                    if filling == Filling::VeggiePatty {
-                                        sick = true;
-                                    } else {
-                                        missing += 1;
-                                        wrong += 1;
-                                    }
+    /// How the customer feels about being given `order` for this, and how close it came
+    fn judge(&self, order: &Bread) -> (Mood, f64) {
+        let mut toppings = order.toppings.clone();
+        let mut score: f64 = 0.0;
+        let mut has_filling = false;
+        let mut sick = false;
+        let mut missing: u32 = 0;
+        let mut wrong: u32 = 0;
+        let mut burnt: u32 = 0;
+        for topping in &self.bread.toppings {
+            // Prefer matching a filling of the same kind before settling for any filling
+            let exact = toppings.iter().position(|other| topping.borrow().topping() == other.borrow().topping());
+            if let Some(i) = exact.or_else(|| toppings.iter().position(|other|
+                matches!((topping.borrow().topping(), other.borrow().topping()),
+                         (Some(Topping::Filling(_)), Some(Topping::Filling(_))))
+            )) {
+                let other = toppings.remove(i);
+                match topping.borrow().topping() {
+                    Some(Topping::Filling(filling)) => {
+                        if let Some(Topping::Filling(other_filling)) = other.borrow().topping() {
+                            if filling == other_filling {
+                                has_filling = true;
+                                if filling.undercooked().is_some_and(|min| other.borrow().cooked()[0] < min) {
+                                    sick = true;
                                 }
+                                if other.borrow().cooked()[1] > filling.burnt() {
+                                    burnt += 1
+                                }
+                                if other.borrow().cooked()[0] > filling.burnt() {
+                                    burnt += 1
+                                }
+                                let given = filling.done_sides(other.borrow().cooked());
+                                let wanted = filling.done_sides(topping.borrow().cooked());
+                                // Customers who asked for it a particular way notice if it isn't
+                                if self.doneness.is_some() && given.iter().zip(&wanted).any(|(g, w)| (g - w).abs() > DONENESS_TOLERANCE) {
+                                    wrong += 1
+                                }
+                                score += 1.0 - given.iter().zip(&wanted).map(|(g, w)| (g - w).powi(2).min(0.1) * 10.0).sum::<f64>()
+                                             - FRESHNESS_PENALTY * (1.0 - other.borrow().freshness());
+                            } else if filling.vegetarian() && !other_filling.vegetarian() {
+                                sick = true;
                             } else {
-                                panic!();
-                            }
-                        },
-                        Some(Topping::Onion) => {
-                            if other.borrow().cooked()[1] > 1.4 {
-                                burnt += 1
-                            }
-                            if other.borrow().cooked()[0] > 1.4 {
-                                burnt += 1
+                                missing += 1;
+                                wrong += 1;
                             }
-                            score += 1.0 - (other.borrow().cooked()[0] - topping.borrow().cooked()[0]).powi(2).min(0.1) * 10.0
-                                         - (other.borrow().cooked()[1] - topping.borrow().cooked()[1]).powi(2).min(0.1) * 10.0;
-                        },
-                        _ => {},
-                    }
-                } else {
-                    missing += 1;
-                    score -= 1.0;
+                        } else {
+                            panic!();
+                        }
+                    },
+                    Some(Topping::Onion) => {
+                        if other.borrow().cooked()[1] > 1.4 {
+                            burnt += 1
+                        }
+                        if other.borrow().cooked()[0] > 1.4 {
+                            burnt += 1
+                        }
+                        if other.borrow().portion() < 0.5 {
+                            missing += 1
+                        }
+                        if self.onion_doneness.is_some() && other.borrow().cooked().iter().zip(&topping.borrow().cooked())
+                                                                  .any(|(g, w)| (g - w).abs() > DONENESS_TOLERANCE) {
+                            wrong += 1
+                        }
+                        // Patchy onions, with some layers much further along than the rest, are marked down
+                        let uneven = other.borrow().cooked()[1] - other.borrow().cooked()[0];
+                        score += 1.0 - (other.borrow().cooked()[0] - topping.borrow().cooked()[0]).powi(2).min(0.1) * 10.0
+                                     - (other.borrow().cooked()[1] - topping.borrow().cooked()[1]).powi(2).min(0.1) * 10.0
+                                     - FRESHNESS_PENALTY * (1.0 - other.borrow().freshness())
+                                     - UNEVEN_PENALTY * (uneven - 0.1).max(0.0);
+                    },
+                    Some(Topping::Condiment(_)) => {
+                        // Customers notice if they got much less or much more sauce than they asked for
+                        let amount = other.borrow().portion() / topping.borrow().portion();
+                        if amount < 0.4 {
+                            missing += 1;
+                        } else if amount > 2.5 {
+                            wrong += 1;
+                        }
+                        score -= amount.ln().abs().min(1.0);
+                    },
+                    _ => {},
                 }
+            } else {
+                missing += 1;
+                score -= 1.0;
             }
-            for incorrect in toppings {
-                wrong += 1;
-                if let Some(Topping::Filling(Filling::Sausage)) = incorrect.borrow().topping() {
-                    if incorrect.borrow().cooked()[0] < 0.7 {
-                        sick = true;
-                    }
-                }
-                if incorrect.borrow().cooked()[1] > 1.4 {
-                    burnt += 1
-                }
-                if incorrect.borrow().cooked()[0] > 1.4 {
-                    burnt += 1
+        }
+        for incorrect in toppings {
+            wrong += 1;
+            if let Some(Topping::Filling(filling)) = incorrect.borrow().topping() {
+                if filling.undercooked().is_some_and(|min| incorrect.borrow().cooked()[0] < min) {
+                    sick = true;
                 }
             }
-            let mut meal: Bread = (*order).clone();
-            meal.set_pos(self.pos);
-            self.meal = Some(meal);
-            let mood = if sick {
-                Some(Mood::Sick)
-            } else if has_filling {
-                let score = score / (self.order.toppings.len() as f64);
-                if score < -0.5 || missing + wrong + burnt > 3 {
-                    Some(Mood::Sad)
-                } else if score < 0.0 || missing + wrong + burnt > 0 {
-                    Some(Mood::Neutral)
-                } else {
-                    Some(Mood::Happy)
+            if incorrect.borrow().cooked()[1] > 1.4 {
+                burnt += 1
+            }
+            if incorrect.borrow().cooked()[0] > 1.4 {
+                burnt += 1
+            }
+        }
+        for condiment in &Condiment::ALL {
+            if let (Some(wanted), Some(given)) = (self.bread.under_onion(*condiment), order.under_onion(*condiment)) {
+                if wanted != given {
+                    wrong += 1;
                 }
+            }
+        }
+        if order.toasted > 1.4 {
+            burnt += 1;
+        } else if (order.toasted - self.bread.toasted).abs() > 0.4 {
+            wrong += 1;
+        }
+        let mood = if sick {
+            Mood::Sick
+        } else if has_filling {
+            let score = score / (self.bread.toppings.len() as f64);
+            if score < -0.5 || missing + wrong + burnt > 3 {
+                Mood::Sad
+            } else if score < 0.0 || missing + wrong + burnt > 0 {
+                Mood::Neutral
             } else {
-                Some(Mood::Sad)
-            };
-            self.mood = mood;
-            mood
+                Mood::Happy
+            }
         } else {
-            None
+            Mood::Sad
+        };
+        (mood, score)
+    }
+
+    /// Width of the summary of this order on the ticket rail
+    fn summary_width(&self) -> f64 {
+        self.bread.toppings.len().max(1) as f64 * RAIL_ICON + RAIL_GAP
+    }
+
+    /// Draw the toppings as a row of icons starting at `pos`, with the doneness wanted underneath
+    fn draw_summary(&self, pos: [f64; 2], context: Context, graphics: &mut G) {
+        let mut x = pos[0] + RAIL_ICON / 2.0;
+        for topping in &self.bread.toppings {
+            let icon = [x, pos[1] - 3.0];
+            match topping.borrow().topping() {
+                Some(Topping::Filling(filling)) => filling.draw_icon(icon, context, graphics),
+                Some(Topping::Onion) => piston_window::circle_arc([COOKED_ONION[0], COOKED_ONION[1], COOKED_ONION[2], 1.0],
+                                                                  1.5,
+                                                                  0.0,
+                                                                  1.5 * std::f64::consts::PI,
+                                                                  Rectangle::centered(icon, [9.0, 9.0]).as_floats(),
+                                                                  context.transform,
+                                                                  graphics),
+                Some(Topping::Condiment(condiment)) => piston_window::ellipse(condiment.colour(),
+                                                                              Rectangle::centered(icon, [6.0, 6.0]).as_floats(),
+                                                                              context.transform,
+                                                                              graphics),
+                None => {},
+            }
+            x += RAIL_ICON;
+        }
+        let centre = [pos[0] + (self.summary_width() - RAIL_GAP) / 2.0, pos[1] + 8.0];
+        let small = context.trans(centre[0], centre[1]).scale(0.5, 0.5).trans(-centre[0], -centre[1]);
+        if let Some(doneness) = self.doneness {
+            doneness.draw(ORANGE, [centre[0] - 12.0, centre[1]], small, graphics);
+        }
+        if let Some(doneness) = self.onion_doneness {
+            doneness.draw([COOKED_ONION[0], COOKED_ONION[1], COOKED_ONION[2], 1.0], [centre[0] + 12.0, centre[1]], small, graphics);
+        }
+    }
+
+    fn draw(&self, context: Context, graphics: &mut G) {
+        self.bread.draw(context, graphics);
+        let pos = self.bread.pos;
+        if let Some(doneness) = self.doneness {
+            doneness.draw(ORANGE, [pos[0], pos[1] - 36.0], context, graphics);
+        }
+        if let Some(doneness) = self.onion_doneness {
+            doneness.draw([COOKED_ONION[0], COOKED_ONION[1], COOKED_ONION[2], 1.0], [pos[0], pos[1] + 33.0], context, graphics);
+        }
+    }
+}
+
+impl Customer {
+    /// A customer ordering `size` breads for themselves and the people with them
+    pub fn new(pos: [f64; 2], menu: &Menu, size: usize) -> Customer {
+        let orders = (0..size).map(|_| Order::new(pos, menu)).collect();
+        Customer::with_orders(pos, orders)
+    }
+
+    fn with_orders(pos: [f64; 2], orders: Vec<Order>) -> Customer {
+        let mut customer = Customer{
+            pos,
+            orders,
+            meals: vec![],
+            mood: None,
+            waited: 0.0,
+            distance: f64::INFINITY,
+            exit: None,
+            palette: Palette::Standard,
+        };
+        customer.set_pos(pos);
+        customer
+    }
+
+    fn group(&self) -> bool {
+        self.orders.len() > 1
+    }
+
+    /// Where each bread of a group order sits on its ticket
+    fn ticket_cell(&self, i: usize) -> [f64; 2] {
+        let columns = self.orders.len().min(2);
+        let rows = self.orders.len().div_ceil(2);
+        let (column, row) = (i % 2, i / 2);
+        [self.pos[0] + ORDER_OFFSET[0] + (column as f64 - (columns - 1) as f64 / 2.0) * TICKET_CELL,
+         self.pos[1] + ORDER_OFFSET[1] + (row as f64 - (rows - 1) as f64 / 2.0) * TICKET_CELL]
+    }
+
+    fn ticket(&self) -> Rectangle {
+        let size = [self.orders.len().min(2) as f64 * TICKET_CELL + 8.0, self.orders.len().div_ceil(2) as f64 * TICKET_CELL + 8.0];
+        Rectangle::centered([self.pos[0] + ORDER_OFFSET[0], self.pos[1] + ORDER_OFFSET[1]], size)
+    }
+
+    /// Meals pile up on the plate as they're handed over
+    fn meal_pos(&self, i: usize) -> [f64; 2] {
+        [self.pos[0] + 5.0 * i as f64, self.pos[1] - 5.0 * i as f64]
+    }
+
+    /// How the customer feels about the whole order, once it has all arrived
+    fn overall_mood(&self) -> Mood {
+        let moods: Vec<Mood> = self.orders.iter().filter_map(|o| o.mood).collect();
+        if moods.contains(&Mood::Sick) {
+            return Mood::Sick;
+        }
+        let rank = moods.iter().map(|m| m.rank()).sum::<u32>() / moods.len() as u32;
+        // Groups lose patience if they're kept waiting too long for everything
+        if self.group() && self.waited > GROUP_PATIENCE * self.orders.len() as f64 {
+            Mood::from_rank((rank - 1).max(1))
+        } else {
+            Mood::from_rank(rank)
+        }
+    }
+}
+
+impl Entity for Customer {
+    fn bounds(&self) -> Rectangle {
+        Rectangle::centered(self.pos, PLATE_SIZE)
+    }
+
+    fn set_pos(&mut self, pos: [f64; 2]) {
+        self.pos = pos;
+        for i in 0..self.orders.len() {
+            let cell = if self.group() { self.ticket_cell(i) } else { [pos[0] + ORDER_OFFSET[0], pos[1] + ORDER_OFFSET[1]] };
+            self.orders[i].bread.set_pos(cell);
+        }
+        for i in 0..self.meals.len() {
+            let meal = self.meal_pos(i);
+            self.meals[i].set_pos(meal);
+        }
+    }
+
+    fn draw(&self, context: Context, graphics: &mut G) {
+        piston_window::ellipse(WHITE,
+                               self.bounds().as_floats(),
+                               context.transform,
+                               graphics);
+        piston_window::ellipse(LIGHT_GREY,
+                               Rectangle::centered(self.pos, [PLATE_SIZE[0] * 0.65, PLATE_SIZE[1] * 0.65]).as_floats(),
+                               context.transform,
+                               graphics);
+
+        let thought_colour = self.mood.map_or(WHITE, |m| m.colour(self.palette));
+
+        if self.group() {
+            // Groups hand over a ticket listing everyone's order, ticking each off as it arrives
+            rounded_rectangle(thought_colour,
+                              self.ticket().as_floats(),
+                              4.0,
+                              context.transform,
+                              graphics);
+            for order in &self.orders {
+                let pos = order.bread.pos;
+                if let Some(mood) = order.mood {
+                    piston_window::ellipse(mood.colour(self.palette),
+                                           Rectangle::centered(pos, [TICKET_CELL - 2.0, TICKET_CELL - 2.0]).as_floats(),
+                                           context.transform,
+                                           graphics);
+                }
+                order.draw(context.trans(pos[0], pos[1]).scale(TICKET_SCALE, TICKET_SCALE).trans(-pos[0], -pos[1]), graphics);
+            }
+        } else {
+            piston_window::ellipse(thought_colour,
+                                   [self.pos[0] + ORDER_OFFSET[0] - 54.0, self.pos[1] + ORDER_OFFSET[1] - 30.0, 60.0, 50.0],
+                                   context.transform,
+                                   graphics);
+            piston_window::ellipse(thought_colour,
+                                   [self.pos[0] + ORDER_OFFSET[0] - 44.0, self.pos[1] + ORDER_OFFSET[1] - 10.0, 55.0, 55.0],
+                                   context.transform,
+                                   graphics);
+            piston_window::ellipse(thought_colour,
+                                   [self.pos[0] + ORDER_OFFSET[0] - 34.0, self.pos[1] + ORDER_OFFSET[1] - 45.0, 70.0, 52.0],
+                                   context.transform,
+                                   graphics);
+            piston_window::ellipse(thought_colour,
+                                   [self.pos[0] + ORDER_OFFSET[0] - 4.0, self.pos[1] + ORDER_OFFSET[1] - 40.0, 50.0, 45.0],
+                                   context.transform,
+                                   graphics);
+            piston_window::ellipse(thought_colour,
+                                   [self.pos[0] + ORDER_OFFSET[0] - 14.0, self.pos[1] + ORDER_OFFSET[1] - 15.0, 60.0, 55.0],
+                                   context.transform,
+                                   graphics);
+
+            piston_window::ellipse(thought_colour,
+                                   [self.pos[0] + ORDER_OFFSET[0] - 50.0, self.pos[1] + ORDER_OFFSET[1] - 53.0, 14.0, 14.0],
+                                   context.transform,
+                                   graphics);
+            piston_window::ellipse(thought_colour,
+                                   [self.pos[0] + ORDER_OFFSET[0] - 65.0, self.pos[1] + ORDER_OFFSET[1] - 74.0, 12.0, 12.0],
+                                   context.transform,
+                                   graphics);
+            piston_window::ellipse(thought_colour,
+                                   [self.pos[0] + ORDER_OFFSET[0] - 67.0, self.pos[1] + ORDER_OFFSET[1] - 100.0, 10.0, 11.0],
+                                   context.transform,
+                                   graphics);
+            piston_window::ellipse(thought_colour,
+                                   [self.pos[0] + ORDER_OFFSET[0] - 60.0, self.pos[1] + ORDER_OFFSET[1] - 126.0, 9.0, 10.0],
+                                   context.transform,
+                                   graphics);
+
+            for order in &self.orders {
+                order.draw(context, graphics);
+            }
+        }
+        for meal in &self.meals {
+            meal.draw(context, graphics);
         }
     }
+
+    fn order_overlap(&self, order: &Bread) -> f64 {
+        if self.mood.is_none() {
+            order.bounds().overlap(&self.bounds())
+        } else {
+            0.0
+        }
+    }
+
+    fn deliver_order(&mut self, order: &Bread) -> Option<Mood> {
+        if self.mood.is_some() || !order.bounds().intersect_rect(&self.bounds()) {
+            return None;
+        }
+        // Groups take the bread as whichever of their outstanding orders it suits best
+        let (i, (mood, _)) = self.orders.iter()
+            .enumerate()
+            .filter(|(_, o)| o.mood.is_none())
+            .map(|(i, o)| (i, o.judge(order)))
+            .max_by(|(_, a), (_, b)| (a.0.rank(), a.1).partial_cmp(&(b.0.rank(), b.1)).unwrap())?;
+        self.orders[i].mood = Some(mood);
+        let mut meal: Bread = (*order).clone();
+        meal.set_pos(self.meal_pos(self.meals.len()));
+        self.meals.push(meal);
+        if self.orders.iter().all(|o| o.mood.is_some()) {
+            self.mood = Some(self.overall_mood());
+        }
+        Some(mood)
+    }
 }
 
-#[derive(PartialEq, Eq)]
-enum Stage {
-    TutorialStart,
-    TutorialSausage,
-    TutorialCooking1,
-    TutorialFlip,
-    TutorialBread,
-    TutorialCooking2,
-    TutorialCombine1,
-    TutorialServe1,
-    Freeplay,
+/// The time of day at the polling place, which sets how busy the queue gets
+pub struct Clock {
+    pos: [f64; 2],
+    hour: f64,
+    running: bool,
+    day: Day,
+}
+
+impl Clock {
+    pub fn new(pos: [f64; 2], day: Day) -> Clock {
+        Clock{
+            pos,
+            hour: day.open,
+            running: false,
+            day,
+        }
+    }
+
+    /// Open the polls and start the day
+    pub fn start(&mut self) {
+        self.running = true;
+    }
+
+    pub fn closed(&self) -> bool {
+        self.hour >= self.day.close
+    }
+
+    /// Whether the polls have opened, even if they've closed again since
+    pub fn started(&self) -> bool {
+        self.running
+    }
+
+    /// Whether customers are still turning up
+    pub fn open(&self) -> bool {
+        self.running && !self.closed()
+    }
+
+    pub fn hour(&self) -> f64 {
+        self.hour
+    }
+}
+
+impl Entity for Clock {
+    fn bounds(&self) -> Rectangle {
+        Rectangle::centered(self.pos, [2.0 * CLOCK_RADIUS, 2.0 * CLOCK_RADIUS])
+    }
+
+    fn update(&mut self, dt: f64) -> Vec<Rc<RefCell<dyn Entity>>> {
+        if self.open() {
+            self.hour = (self.hour + dt * (self.day.close - self.day.open) / self.day.day_length)
+                .min(self.day.close);
+        }
+        vec![]
+    }
+
+    fn draw(&self, context: Context, graphics: &mut G) {
+        let rim = if self.closed() { SAD } else { DARK_GREY };
+        piston_window::ellipse(rim,
+                               self.bounds().as_floats(),
+                               context.transform,
+                               graphics);
+        piston_window::ellipse(WHITE,
+                               Rectangle::centered(self.pos, [2.0 * CLOCK_RADIUS - 6.0, 2.0 * CLOCK_RADIUS - 6.0]).as_floats(),
+                               context.transform,
+                               graphics);
+        for i in 0..12 {
+            let angle = i as f64 * std::f64::consts::PI / 6.0;
+            let (s, c) = angle.sin_cos();
+            piston_window::line(DARK_GREY,
+                                if i % 3 == 0 { 1.0 } else { 0.5 },
+                                [self.pos[0] + s * (CLOCK_RADIUS - 8.0), self.pos[1] - c * (CLOCK_RADIUS - 8.0),
+                                 self.pos[0] + s * (CLOCK_RADIUS - 4.0), self.pos[1] - c * (CLOCK_RADIUS - 4.0)],
+                                context.transform,
+                                graphics);
+        }
+        let hands = [
+            (self.hour / 12.0, 0.5, 1.5),
+            (self.hour.fract(), 0.75, 1.0),
+        ];
+        for (turn, length, width) in &hands {
+            let (s, c) = (turn * 2.0 * std::f64::consts::PI).sin_cos();
+            piston_window::line(BLACK,
+                                *width,
+                                [self.pos[0], self.pos[1],
+                                 self.pos[0] + s * length * CLOCK_RADIUS, self.pos[1] - c * length * CLOCK_RADIUS],
+                                context.transform,
+                                graphics);
+        }
+    }
+
+    fn draw_text(&self, context: Context, font: &mut Font, graphics: &mut G) {
+        let minutes = (self.hour * 60.0).floor() as u32;
+        let time = format!("{}:{:02}", minutes / 60, minutes % 60);
+        let colour = if self.closed() { SAD } else { WHITE };
+        font.draw(&time,
+                  [self.pos[0], self.pos[1] + CLOCK_RADIUS + CLOCK_TEXT + 2.0],
+                  CLOCK_TEXT,
+                  colour,
+                  Align::Centre,
+                  context,
+                  graphics);
+    }
+}
+
+/// Label each point of a polyline with the distance along it, for use with `interpolate_path`
+fn measure_path(points: &[[f64; 2]]) -> Vec<([f64; 2], f64)> {
+    let mut distance = 0.0;
+    points.iter().enumerate().map(|(i, p)| {
+        if i > 0 {
+            distance += (p[0] - points[i - 1][0]).hypot(p[1] - points[i - 1][1]);
+        }
+        (*p, distance)
+    }).collect()
 }
 
 pub struct Queue {
     head: [f64; 2],
     entry: [f64; 2],
+    path: Vec<([f64; 2], f64)>,
+    exit: Vec<[f64; 2]>,
     max_len: usize,
+    spacing: f64,
+    arrivals: Arrivals,
     customers: Vec<Customer>,
-    stage: Stage,
-    pointer: Option<Pointer>,
-    sausage: Option<Rc<RefCell<dyn Entity>>>,
-    bread: Option<Rc<RefCell<dyn Entity>>>,
+    /// How many breads have been handed over
+    served: usize,
+    clock: Rc<RefCell<Clock>>,
+    /// Colours for how the customers feel
+    palette: Palette,
 }
 
 impl Queue {
-    pub fn new(head: [f64; 2], entry: [f64; 2], max_len: usize) -> Queue {
+    /// A queue lining up along `path` from the head of the queue back to where customers arrive,
+    /// with served customers walking away along `exit`
+    pub fn new(path: Vec<[f64; 2]>, exit: Vec<[f64; 2]>, max_len: usize, arrivals: Arrivals, clock: Rc<RefCell<Clock>>) -> Queue {
         Queue{
-            head, entry, max_len,
+            head: path[0],
+            entry: path[path.len() - 1],
+            path: measure_path(&path),
+            exit,
+            max_len,
+            spacing: QUEUE_SPACING,
+            arrivals,
+            clock,
             customers: Vec::with_capacity(max_len),
-            stage: Stage::TutorialStart,
-            pointer: None,
-            sausage: None,
-            bread: None,
+            served: 0,
+            palette: Palette::Standard,
+        }
+    }
+
+    /// Space customers further apart, so those queueing up the screen don't stand on the orders in front
+    pub fn with_spacing(self, spacing: f64) -> Queue {
+        Queue{spacing, ..self}
+    }
+
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.palette = palette;
+        for customer in &mut self.customers {
+            customer.palette = palette;
         }
     }
+
+    fn join(&mut self, customer: Customer) {
+        self.customers.push(Customer{palette: self.palette, ..customer});
+    }
+
+    /// Bring on a new customer with a random order straight away
+    pub fn spawn(&mut self) {
+        let customer = Customer::new(self.entry, &self.arrivals.menu, self.arrivals.group_size());
+        self.join(customer);
+    }
+
+    /// Bring on a customer wanting exactly `orders`
+    pub fn arrive(&mut self, orders: &[OrderSpec]) {
+        let orders = orders.iter().map(|o| Order::exact(self.entry, o)).collect();
+        self.join(Customer::with_orders(self.entry, orders));
+    }
+
+    /// Where customers are served
+    pub fn head(&self) -> [f64; 2] {
+        self.head
+    }
+
+    pub fn served(&self) -> usize {
+        self.served
+    }
 }
 
 impl Entity for Queue {
     fn bounds(&self) -> Rectangle {
-        Rectangle::new([self.head[0] - 50.0, self.head[1] - 50.0], [100.0 + (self.entry[0] - self.head[0]), 100.0 + (self.entry[1] - self.head[1])])
+        self.path.iter()
+            .map(|(p, _)| Rectangle::centered(*p, [100.0, 100.0]))
+            .fold(Rectangle::centered(self.head, [100.0, 100.0]), |r, s| r.union(&s))
     }
 
     fn draw(&self, context: Context, graphics: &mut G) {
         for customer in &self.customers {
             customer.draw(context, graphics);
         }
-
-        if let Some(pointer) = &self.pointer {
-            pointer.draw(context, graphics);
-        }
     }
 
     fn update(&mut self, dt: f64) -> Vec<Rc<RefCell<dyn Entity>>> {
-        match self.stage {
-            Stage::Freeplay => {
-                if self.customers.len() < self.max_len && rand::random::<f64>() < dt * CUSTOMERS_PER_SECOND {
-                    self.customers.push(Customer::new(self.entry));
-                }
-            },
-            Stage::TutorialStart => {
-                self.customers.push(Customer{
-                    pos: self.entry,
-                    order: Bread{
-                        pos: [self.entry[0] + ORDER_OFFSET[0], self.entry[1] + ORDER_OFFSET[1]],
-                        toppings: vec![Rc::new(RefCell::new(Cookable::with_cooked(
-                            Filling::Sausage,
-                            [self.entry[0] + ORDER_OFFSET[0], self.entry[1] + ORDER_OFFSET[1]],
-                            1.0,
-                        )))],
-                    },
-                    meal: None,
-                    mood: None,
-                });
-                self.stage = Stage::TutorialSausage;
-                self.pointer = Some(Pointer::new([120.0, 270.0], [300.0, 270.0]))
-            },
-            Stage::TutorialSausage => {
-                if !self.sausage.is_none() {
-                    self.pointer = None;
-                    self.stage = Stage::TutorialCooking1;
-                }
-            },
-            Stage::TutorialCooking1 => {
-                if let Some(sausage) = &self.sausage {
-                    if sausage.borrow().cooked()[1] > 0.9 {
-                        self.pointer = Some(Pointer::new(sausage.borrow().get_pos(), [sausage.borrow().get_pos()[0] - 20.0, sausage.borrow().get_pos()[1]]));
-                        self.stage = Stage::TutorialFlip;
-                    }
-                }
-            },
-            Stage::TutorialFlip => {},
-            Stage::TutorialBread => {
-                if let Some(_) = &self.bread {
-                    if let Some(_) = &self.sausage {
-                        self.pointer = None;
-                        self.stage = Stage::TutorialCooking2;
-                    }
-                }
+        let rate = {
+            let clock = self.clock.borrow();
+            if clock.open() { self.arrivals.rate(clock.hour()) } else { 0.0 }
+        };
+        if self.customers.len() < self.max_len && rand::random::<f64>() < dt * rate {
+            self.spawn();
+        }
+
+        // Served customers walk off along the exit, holding up the queue until they're clear of the head
+        let mut front: f64 = 0.0;
+        let exit = &self.exit;
+        for customer in self.customers.iter_mut().filter(|c| c.mood.is_some()) {
+            if customer.exit.is_none() {
+                let mut path = vec![customer.pos];
+                path.extend(exit);
+                customer.exit = Some(measure_path(&path));
+                customer.distance = 0.0;
+            }
+            customer.distance += QUEUE_SPEED * dt * 2.0;
+            let pos = interpolate_path(customer.exit.as_ref().unwrap(), customer.distance);
+            customer.set_pos(pos);
+            front = front.max(self.spacing - customer.distance);
+        }
+
+        // Everyone else shuffles forward along the path, keeping their distance from the customer in front
+        let (path, spacing) = (&self.path, self.spacing);
+        self.customers.iter_mut().filter(|c| c.mood.is_none()).fold(front, |target, customer| {
+            let end = path[path.len() - 1].1;
+            customer.waited += dt;
+            customer.distance = (customer.distance.min(end) - QUEUE_SPEED * dt).max(target);
+            customer.set_pos(interpolate_path(path, customer.distance));
+            customer.distance + spacing
+        });
+        self.customers.retain(|c| c.exit.as_ref().is_none_or(|exit| c.distance < exit[exit.len() - 1].1));
+    
+        vec![]
+    }
 
-            },
-            Stage::TutorialCooking2 => {
-                if let Some(sausage) = &self.sausage {
-                    if let Some(bread) = &self.bread {
-                        if sausage.borrow().cooked()[0] > 0.9 {
-                            self.pointer = Some(Pointer::new(sausage.borrow().get_pos(), bread.borrow().get_pos()));
-                            self.stage = Stage::TutorialCombine1;
-                        }
-                    }
-                }
-            },
-            Stage::TutorialCombine1 => {},
-            Stage::TutorialServe1 => {},
-            // Stage::TutorialStage2 => {
-            //     self.customers.push(Customer{
-            //         pos: self.entry,
-            //         order: Bread{
-            //             pos: [self.entry[0] + ORDER_OFFSET[0], self.entry[1] + ORDER_OFFSET[1]],
-            //             toppings: vec![Rc::new(RefCell::new(Cookable::with_cooked(
-            //                 Filling::Sausage,
-            //                 pos: [self.entry[0] + ORDER_OFFSET[0] - SAUSAGE_OFFSET, self.entry[1] + ORDER_OFFSET[1]],
-            //                 1.0,
-            //             ))), Rc::new(RefCell::new(Cookable::with_cooked(
-            //                 Filling::Sausage,
-            //                 pos: [self.entry[0] + ORDER_OFFSET[0] + SAUSAGE_OFFSET, self.entry[1] + ORDER_OFFSET[1]],
-            //                 1.0,
-            //             ))), Rc::new(RefCell::new(Onion::with_cooked(
-            //                 pos: [self.entry[0] + ORDER_OFFSET[0], self.entry[1] + ORDER_OFFSET[1]],
-            //                 1.0,
-            //             ))), Rc::new(RefCell::new(Squirt::new(
-            //                 Condiment::Sauce,
-            //                 pos: [self.entry[0] + ORDER_OFFSET[0], self.entry[1] + ORDER_OFFSET[1]],
-            //             )))],
-            //         },
-            //         meal: None,
-            //         mood: None,
-            //     });
-            //     self.stage = Stage::TutorialChop;
-            // },
-        }
-
-        if let Some(bread) = &self.bread {
-            if let Some(order) = bread.borrow().order() {
-                if self.stage != Stage::TutorialServe1 {
-                    if order.toppings.iter().filter(|t| t.borrow().topping() == Some(Topping::Filling(Filling::Sausage))).count() > 0 {
-                        self.pointer = Some(Pointer::new(bread.borrow().get_pos(), self.head));
-                        self.stage = Stage::TutorialServe1;
-                    }
-                }
+    /// How much of the order covers the waiting customer it is dropped most squarely on
+    fn order_overlap(&self, order: &Bread) -> f64 {
+        self.customers.iter()
+            .map(|c| c.order_overlap(order))
+            .fold(0.0, f64::max)
+    }
+
+    fn deliver_order(&mut self, order: &Bread) -> Option<Mood> {
+        let best = self.customers.iter()
+            .enumerate()
+            .map(|(i, c)| (i, c.order_overlap(order)))
+            .filter(|(_, overlap)| *overlap > 0.0)
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+            .map(|(i, _)| i);
+        if let Some(i) = best {
+            if let Some(mood) = self.customers[i].deliver_order(order) {
+                self.served += 1;
+                return Some(mood);
             }
         }
+        None
+    }
+}
 
-        if let Some(pointer) = &mut self.pointer {
-            pointer.update(dt);
-        }
+/// A strip along the top of the screen with a ticket for every customer waiting, so orders can be
+/// planned before the customer is in sight
+pub struct TicketRail {
+    bounds: Rectangle,
+    queues: Vec<Rc<RefCell<Queue>>>,
+}
 
-        let del = [self.head[0] - self.entry[0], self.head[1] - self.entry[1]];
-        let norm = (del[0] * del[0] + del[1] * del[1]).sqrt();
-        let del = [del[0] / norm, del[1] / norm];
-        let head = self.head;
-        self.customers.iter_mut().fold(None as Option<&Customer>, |prev, customer| {
-            if customer.mood.is_none() {
-                let target = if let Some(prev) = prev {
-                    [prev.pos[0] - del[0] * QUEUE_SPACING, prev.pos[1] - del[1] * QUEUE_SPACING]
-                } else {
-                    head
-                };
-                let maxdel = [target[0] - customer.pos[0], target[1] - customer.pos[1]];
-                if maxdel[0] / del[0] > 0.0 {
-                    if maxdel[0] / del[0] > QUEUE_SPEED * dt {
-                        customer.set_pos([
-                            customer.pos[0] + del[0] * QUEUE_SPEED * dt,
-                            customer.pos[1] + del[1] * QUEUE_SPEED * dt,
-                        ]);
-                    } else {
-                        customer.set_pos(target);
-                    }
-                }
-                Some(customer)
-            } else {
-                customer.set_pos([
-                    customer.pos[0],
-                    customer.pos[1] - QUEUE_SPEED * dt * 2.0,
-                ]);
-                if customer.pos[1] > head[1] - 60.0 {
-                    Some(customer)
-                } else {
-                    prev
+impl TicketRail {
+    pub fn new(pos: [f64; 2], width: f64, queues: Vec<Rc<RefCell<Queue>>>) -> TicketRail {
+        TicketRail{
+            bounds: Rectangle::new(pos, [width, RAIL_HEIGHT]),
+            queues,
+        }
+    }
+
+    /// Where the tickets of the customers still waiting in each queue go along the rail, as their left
+    /// edge and width, with how many don't fit and where the marker counting them goes. Each queue
+    /// leaves room for the markers of the queues after it.
+    fn layout(&self) -> Vec<(Vec<[f64; 2]>, usize, f64)> {
+        let [left, _, width, _] = self.bounds.as_floats();
+        let mut x = left + RAIL_GAP;
+        let mut layout = vec![];
+        for (n, queue) in self.queues.iter().enumerate() {
+            let queue = queue.borrow();
+            let end = left + width - (self.queues.len() - n - 1) as f64 * (RAIL_OVERFLOW + 3.0 * RAIL_GAP);
+            let waiting: Vec<&Customer> = queue.customers.iter().filter(|c| c.mood.is_none()).collect();
+            let mut tickets = vec![];
+            for customer in &waiting {
+                let ticket_width = customer.orders.iter()
+                    .filter(|o| o.mood.is_none())
+                    .map(|o| o.summary_width())
+                    .sum::<f64>() + RAIL_GAP;
+                let room = if tickets.len() + 1 == waiting.len() { end } else { end - RAIL_OVERFLOW - RAIL_GAP };
+                if x + ticket_width > room {
+                    break;
                 }
+                tickets.push([x, ticket_width]);
+                x += ticket_width + RAIL_GAP;
             }
-        });
-        self.customers.retain(|c| c.mood.is_none() || c.pos[1] > head[1] - 200.0);
-    
-        vec![]
+            let hidden = waiting.len() - tickets.len();
+            layout.push((tickets, hidden, x));
+            if hidden > 0 {
+                x += RAIL_OVERFLOW + RAIL_GAP;
+            }
+            x += 2.0 * RAIL_GAP;
+        }
+        layout
     }
+}
 
-    fn other_dropped(&mut self, other: &Rc<RefCell<dyn Entity>>) {
-        if other.borrow().topping() == Some(Topping::Filling(Filling::Sausage)) {
-            self.sausage = Some(other.clone());
-        }
-        if !other.borrow().order().is_none() {
-            self.bread = Some(other.clone());
-        }
-        match self.stage {
-            Stage::TutorialFlip => {
-                if let Some(sausage) = &self.sausage {
-                    if Rc::ptr_eq(other, sausage) {
-                        self.pointer = Some(Pointer::new([30.0, 400.0], [120.0, 340.0]));
-                        self.stage = Stage::TutorialBread;
-                    }
+impl Entity for TicketRail {
+    fn bounds(&self) -> Rectangle {
+        self.bounds
+    }
+
+    fn draw(&self, context: Context, graphics: &mut G) {
+        let [left, top, width, height] = self.bounds.as_floats();
+        piston_window::rectangle(DARK_GREY,
+                                 [left, top, width, height],
+                                 context.transform,
+                                 graphics);
+        for (queue, (tickets, _, _)) in self.queues.iter().zip(self.layout()) {
+            let queue = queue.borrow();
+            let waiting = queue.customers.iter().filter(|c| c.mood.is_none());
+            for (i, (customer, [x, ticket_width])) in waiting.zip(tickets).enumerate() {
+                let ticket = [x, top + 2.0, ticket_width, height - 4.0];
+                // The customer at the head of each queue is the one to serve next
+                if i == 0 {
+                    rounded_rectangle(ORANGE,
+                                      [ticket[0] - 1.5, ticket[1] - 1.5, ticket[2] + 3.0, ticket[3] + 3.0],
+                                      3.0,
+                                      context.transform,
+                                      graphics);
                 }
-            },
-            _ => {},
+                rounded_rectangle(WHITE,
+                                  ticket,
+                                  2.0,
+                                  context.transform,
+                                  graphics);
+                let mut item = x + RAIL_GAP;
+                for order in customer.orders.iter().filter(|o| o.mood.is_none()) {
+                    order.draw_summary([item, top + height / 2.0], context, graphics);
+                    item += order.summary_width();
+                }
+            }
         }
     }
 
-    fn deliver_order(&mut self, order: &Bread) -> Option<Mood> {
-        for customer in &mut self.customers {
-            if let Some(mood) = customer.deliver_order(order) {
-                if self.stage != Stage::Freeplay {
-                    self.stage = Stage::Freeplay;
-                    self.pointer = None;
-                    self.sausage = None;
-                    self.bread = None;
-                    self.customers.push(Customer::new(self.entry));
-                }
-                return Some(mood);
+    /// Count the customers whose tickets don't fit on the rail
+    fn draw_text(&self, context: Context, font: &mut Font, graphics: &mut G) {
+        let [_, top, _, height] = self.bounds.as_floats();
+        for (_, hidden, x) in self.layout() {
+            if hidden > 0 {
+                font.draw(&format!("+{}", hidden),
+                          [x + RAIL_OVERFLOW / 2.0, top + (height + RAIL_OVERFLOW_TEXT * 0.7) / 2.0],
+                          RAIL_OVERFLOW_TEXT,
+                          WHITE,
+                          Align::Centre,
+                          context,
+                          graphics);
             }
         }
-        None
     }
 }
 
@@ -1632,27 +3245,94 @@ const POINTER_PAUSE: f64 = 0.5;
 const POINTER_HIDE: f64 = 2.0;
 const POINTER_COLOUR: [f32; 4] = [0.0, 0.0, 0.7, 0.8];
 const POINTER_R: f64 = 15.0;
+const POINTER_PRESS: f64 = 5.0;
+const PULSE_PERIOD: f64 = 1.0;
+const PULSE_GROWTH: f64 = 20.0;
+const POINTER_CAPTION: f64 = 12.0;
+const CHOP_DEMO: f64 = 50.0;
+const STIR_DEMO: f64 = 15.0;
+const FLIP_DEMO: f64 = 30.0;
+
+/// A move the pointer can demonstrate on the spot
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Gesture {
+    /// Hold the knife and stroke it up and down
+    Chop,
+    /// Small circles
+    Stir,
+    /// Pick up and put straight back down
+    Flip,
+}
 
+impl Gesture {
+    fn path(&self, pos: [f64; 2]) -> Vec<[f64; 2]> {
+        match self {
+            Gesture::Chop => (0..7).map(|i| [pos[0], pos[1] + if i % 2 == 1 { CHOP_DEMO } else { 0.0 }]).collect(),
+            Gesture::Stir => (0..=24).map(|i| {
+                let (s, c) = (i as f64 * std::f64::consts::PI / 6.0).sin_cos();
+                [pos[0] + STIR_DEMO * s, pos[1] - STIR_DEMO * c]
+            }).collect(),
+            Gesture::Flip => vec![pos, [pos[0], pos[1] - FLIP_DEMO], pos],
+        }
+    }
+}
+
+/// A hint showing the player where to drag, optionally pulsing where to look and saying why
 pub struct Pointer {
     pos: [f64; 2],
-    start: [f64; 2],
-    end: [f64; 2],
+    path: Vec<([f64; 2], f64)>,
     age: f64,
     duration: f64,
+    pulse: bool,
+    caption: Option<String>,
     expired: bool,
 }
 
 impl Pointer {
-    fn new(start: [f64; 2], end: [f64; 2]) -> Pointer {
+    pub fn new(start: [f64; 2], end: [f64; 2]) -> Pointer {
+        Pointer::along(vec![start, end])
+    }
+
+    /// Drag along each of `points` in turn
+    pub fn along(points: Vec<[f64; 2]>) -> Pointer {
+        let path = measure_path(&points);
         Pointer{
-            pos: start,
-            start,
-            end,
+            pos: points[0],
+            duration: path[path.len() - 1].1 / POINTER_SPEED,
+            path,
             age: 0.0,
-            duration: ((end[0] - start[0]).powi(2) + (end[1] - start[1]).powi(2)).sqrt() / POINTER_SPEED,
+            pulse: false,
+            caption: None,
             expired: false,
         }
     }
+
+    pub fn gesture(gesture: Gesture, pos: [f64; 2]) -> Pointer {
+        Pointer::along(gesture.path(pos))
+    }
+
+    /// Just draw attention to `pos`
+    pub fn pulse(pos: [f64; 2]) -> Pointer {
+        Pointer::along(vec![pos]).with_pulse()
+    }
+
+    /// Pulse where the pointer ends up
+    pub fn with_pulse(self) -> Pointer {
+        Pointer{pulse: true, ..self}
+    }
+
+    pub fn with_caption(self, caption: String) -> Pointer {
+        Pointer{caption: Some(caption), ..self}
+    }
+
+    fn target(&self) -> [f64; 2] {
+        self.path[self.path.len() - 1].0
+    }
+
+    fn pressed(&self) -> bool {
+        self.age > POINTER_PAUSE && self.age < self.duration + POINTER_PAUSE
+    }
 }
 
 impl Entity for Pointer {
@@ -1662,17 +3342,8 @@ impl Entity for Pointer {
 
     fn update(&mut self, dt: f64) -> Vec<Rc<RefCell<dyn Entity>>> {
         self.age = (self.age + dt) % (self.duration + 2.0 * POINTER_PAUSE + POINTER_HIDE);
-        self.pos = if self.age < POINTER_PAUSE {
-            self.start
-        } else if self.age > self.duration + POINTER_PAUSE {
-            self.end
-        } else {
-            let s = (self.age - POINTER_PAUSE) / self.duration;
-            [
-                self.start[0] * (1.0 - s) + self.end[0] * s,
-                self.start[1] * (1.0 - s) + self.end[1] * s,
-            ]
-        };
+        let distance = (self.age - POINTER_PAUSE).clamp(0.0, self.duration) * POINTER_SPEED;
+        self.pos = interpolate_path(&self.path, distance);
         vec![]
     }
 
@@ -1681,11 +3352,210 @@ impl Entity for Pointer {
     }
 
     fn draw(&self, context: Context, graphics: &mut G) {
+        if self.pulse {
+            let phase = (self.age % PULSE_PERIOD) / PULSE_PERIOD;
+            let colour = [POINTER_COLOUR[0], POINTER_COLOUR[1], POINTER_COLOUR[2], POINTER_COLOUR[3] * (1.0 - phase as f32)];
+            piston_window::Ellipse::new_border(colour, 1.5)
+                .draw(Rectangle::centered(self.target(), [2.0 * (POINTER_R + phase * PULSE_GROWTH); 2]).as_floats(),
+                      &context.draw_state,
+                      context.transform,
+                      graphics);
+        }
         if self.age < self.duration + 2.0 * POINTER_PAUSE {
             piston_window::ellipse(POINTER_COLOUR,
                                    self.bounds().as_floats(),
                                    context.transform,
                                    graphics);
+            // A ring around the dot while the button is held down
+            if self.pressed() {
+                piston_window::Ellipse::new_border(POINTER_COLOUR, 1.5)
+                    .draw(Rectangle::centered(self.pos, [2.0 * (POINTER_R + POINTER_PRESS); 2]).as_floats(),
+                          &context.draw_state,
+                          context.transform,
+                          graphics);
+            }
+        }
+    }
+
+    fn draw_text(&self, context: Context, font: &mut Font, graphics: &mut G) {
+        if let Some(caption) = &self.caption {
+            let target = self.target();
+            font.caption(caption, [target[0], target[1] - POINTER_R - POINTER_CAPTION - PULSE_GROWTH], POINTER_CAPTION, context, graphics);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity<E: Entity + 'static>(e: E) -> Rc<RefCell<dyn Entity>> {
+        Rc::new(RefCell::new(e))
+    }
+
+    #[test]
+    fn pick_a_filling_off_bread() {
+        let mut bread = Bread::new([100.0, 100.0]);
+        bread.add_topping(&entity(Cookable::new(Filling::Sausage, [0.0, 0.0])));
+        bread.add_topping(&entity(Cookable::new(Filling::Sausage, [0.0, 0.0])));
+        match bread.select([100.0 + SAUSAGE_OFFSET, 100.0]) {
+            Selection::New(sausage) => assert!(sausage.borrow().topping() == Some(Topping::Filling(Filling::Sausage))),
+            _ => panic!("Expected to pick up a sausage"),
+        }
+        assert_eq!(bread.count(Topping::Filling(Filling::Sausage)), 1);
+        // The sausage left behind moves back to the middle
+        assert_eq!(bread.toppings[0].borrow().get_pos(), [100.0, 100.0]);
+    }
+
+    #[test]
+    fn pick_a_portion_of_onion_off_bread() {
+        let mut pile = Onion::with_cooked([0.0, 0.0], 1.0);
+        pile.set_heat(1.0);
+        pile.add_topping(&entity(Onion::with_cooked([0.0, 0.0], 1.0)));
+        assert!(pile.portion() > ONION_SPLIT);
+        let pile = Rc::new(RefCell::new(pile));
+        let mut bread = Bread::new([100.0, 100.0]);
+        bread.add_topping(&entity(Cookable::new(Filling::Sausage, [0.0, 0.0])));
+        bread.add_topping(&(pile.clone() as Rc<RefCell<dyn Entity>>));
+
+        // Somewhere along a piece of onion that's over the bread
+        let pos = {
+            let pile = pile.borrow();
+            pile.layers.iter().flatten()
+                .map(|piece| {
+                    let r = piece.rect[2] / 2.0;
+                    let middle = (piece.start + piece.end) / 2.0;
+                    let centre = piece.centre(pile.pos);
+                    [centre[0] + r * middle.cos(), centre[1] + r * middle.sin()]
+                })
+                .find(|pos| bread.bounds().intersect_point(*pos))
+                .unwrap()
+        };
+        match bread.select(pos) {
+            Selection::New(part) => {
+                assert!(part.borrow().topping() == Some(Topping::Onion));
+                assert_eq!(part.borrow().portion(), 1.0);
+            },
+            _ => panic!("Expected to pick up some onion"),
+        }
+        // The rest of the pile stays on the bread with the sausage
+        assert_eq!(bread.toppings.len(), 2);
+        assert_eq!(bread.count(Topping::Onion), 1);
+        assert_eq!(pile.borrow().portion(), 1.0);
+    }
+
+    #[test]
+    fn food_goes_stale_slower_on_the_warming_tray() {
+        let mut held = Temperature::new();
+        let mut cold = Temperature::new();
+        let mut cooking = Temperature::new();
+        for _ in 0..600 {
+            held.update(0.1, WARMING_HEAT, 1.0);
+            cold.update(0.1, 0.0, 1.0);
+            cooking.update(0.1, 0.05, 1.0);
+        }
+        assert!(held.stale > 0.0);
+        assert!(held.stale < cold.stale);
+        assert_eq!(held.warmth, 1.0);
+        assert_eq!(cooking.stale, 0.0);
+    }
+
+    #[test]
+    fn every_condiment_is_described() {
+        let condiments = load_condiments(CONDIMENT_INFO);
+        for condiment in Condiment::ALL.iter() {
+            let info = &condiments[condiment];
+            assert!(info.popularity > 0.0);
+            assert!(info.placement.abs() < 1.0);
+        }
+        assert_eq!(condiments.len(), Condiment::ALL.len());
+    }
+
+    #[test]
+    fn squeezing_more_sauce_joins_the_squirt() {
+        let mut bread = Bread::new([100.0, 100.0]);
+        assert!(bread.use_tool(Tool::Bottle(Condiment::Sauce), [100.0, 100.0]));
+        assert!(bread.use_tool(Tool::Bottle(Condiment::Sauce), [110.0, 100.0]));
+        assert!(bread.use_tool(Tool::Bottle(Condiment::Mustard), [100.0, 100.0]));
+        assert!(!bread.use_tool(Tool::Spatula, [100.0, 100.0]));
+        assert!(!bread.use_tool(Tool::Bottle(Condiment::Sauce), [300.0, 100.0]));
+        assert_eq!(bread.count(Topping::Condiment(Condiment::Sauce)), 1);
+        assert_eq!(bread.count(Topping::Condiment(Condiment::Mustard)), 1);
+    }
+
+    #[test]
+    fn chopping_board_chops_at_least_one_portion() {
+        assert_eq!(ChoppingBoard::new([0.0, 0.0], 0).portions, 1);
+        assert_eq!(ChoppingBoard::new([0.0, 0.0], 4).portions, 4);
+    }
+
+    #[test]
+    fn ticket_rail_counts_the_customers_that_dont_fit() {
+        let clock = Rc::new(RefCell::new(Clock::new([0.0, 0.0], Day::load())));
+        let queue = |customers| {
+            let arrivals = Arrivals::regular();
+            let mut queue = Queue::new(vec![[0.0, 0.0], [100.0, 0.0]], vec![[0.0, 100.0]], 20, Arrivals::regular(), clock.clone());
+            for _ in 0..customers {
+                queue.customers.push(Customer::new([0.0, 0.0], &arrivals.menu, 1));
+            }
+            Rc::new(RefCell::new(queue))
+        };
+        let rail = TicketRail::new([0.0, 0.0], 300.0, vec![queue(20), queue(2)]);
+        let layout = rail.layout();
+        assert!(layout[0].1 > 0);
+        assert_eq!(layout[0].0.len() + layout[0].1, 20);
+        assert_eq!(layout[1].0.len() + layout[1].1, 2);
+        // The second queue still gets some of the rail after the first overflows
+        let (tickets, hidden, marker) = &layout[1];
+        let end = tickets.last().map_or(*marker, |t| t[0] + t[1]) + if *hidden > 0 { RAIL_OVERFLOW } else { 0.0 };
+        assert!(end <= 300.0);
+        assert!(*marker > layout[0].2);
+    }
+
+    /// An order for a single `filling` cooked to `doneness`, with nothing else on it
+    fn order_for(filling: Filling, doneness: Doneness) -> Order {
+        let mut bread = Bread::new([0.0, 0.0]);
+        bread.add_topping(&entity(Cookable::with_cooked(filling, [0.0, 0.0], doneness.target())));
+        Order{bread, doneness: Some(doneness), onion_doneness: None, mood: None}
+    }
+
+    /// Cook `filling` on the hotplate, turning it over halfway if it can be, until its judged sides reach `target`
+    fn cook(filling: Filling, target: f64) -> Bread {
+        let mut food = Cookable::new(filling, [0.0, 0.0]);
+        food.set_heat(1.0);
+        let mut flipped = false;
+        while filling.done_sides(food.cooked()).iter().cloned().fold(f64::INFINITY, f64::min) < target {
+            food.update(0.01);
+            if filling.flips() && !flipped && food.cooked()[1] >= target {
+                food.grab();
+                food.set_heat(1.0);
+                flipped = true;
+            }
+        }
+        food.set_heat(0.0);
+        let mut bread = Bread::new([0.0, 0.0]);
+        bread.add_topping(&entity(food));
+        bread
+    }
+
+    #[test]
+    fn eggs_can_be_cooked_to_order() {
+        for doneness in &[Doneness::Rare, Doneness::Medium, Doneness::WellDone] {
+            let bread = cook(Filling::Egg, doneness.target());
+            assert!(bread.toppings[0].borrow().cooked()[0] > Filling::Egg.undercooked().unwrap());
+            assert!(bread.toppings[0].borrow().cooked()[1] < Filling::Egg.burnt());
+            assert!(order_for(Filling::Egg, *doneness).judge(&bread).0 == Mood::Happy);
+        }
+    }
+    #[test]
+    fn both_sides_of_a_sausage_count_towards_how_done_it_is() {
+        let order = order_for(Filling::Sausage, Doneness::Medium);
+        for (bottom, happy) in &[(Doneness::Medium, true), (Doneness::ExtraCrispy, false)] {
+            let mut sausage = Cookable::with_cooked(Filling::Sausage, [0.0, 0.0], Doneness::Medium.target());
+            sausage.bottom_cooked = bottom.target();
+            let mut bread = Bread::new([0.0, 0.0]);
+            bread.add_topping(&entity(sausage));
+            assert_eq!(order.judge(&bread).0 == Mood::Happy, *happy);
         }
     }
 }
@@ -24,6 +24,13 @@ impl Rectangle {
             && rect.0[1] + rect.0[3] > self.0[1] && rect.0[1] < self.0[1] + self.0[3]
     }
 
+    /// Area of the region covered by both rectangles
+    pub fn overlap(&self, rect: &Rectangle) -> f64 {
+        let w = (self.0[0] + self.0[2]).min(rect.0[0] + rect.0[2]) - self.0[0].max(rect.0[0]);
+        let h = (self.0[1] + self.0[3]).min(rect.0[1] + rect.0[3]) - self.0[1].max(rect.0[1]);
+        w.max(0.0) * h.max(0.0)
+    }
+
     #[inline]
     pub fn centre(&self) ->[f64; 2] {
         [self.0[0] + self.0[2] / 2.0, self.0[1] + self.0[3] / 2.0]
@@ -42,3 +49,367 @@ impl Rectangle {
         self.0
     }
 }
+
+/// Rectangle rotated by `angle` radians about its centre
+#[derive(Clone, Copy, Debug)]
+pub struct OrientedRectangle {
+    centre: [f64; 2],
+    size: [f64; 2],
+    angle: f64,
+}
+
+impl OrientedRectangle {
+    #[inline]
+    pub fn new(centre: [f64; 2], size: [f64; 2], angle: f64) -> OrientedRectangle {
+        OrientedRectangle{centre, size, angle}
+    }
+
+    /// Unit vectors along the width and height of the rectangle
+    #[inline]
+    pub fn axes(&self) -> [[f64; 2]; 2] {
+        let (sin, cos) = self.angle.sin_cos();
+        [[cos, sin], [-sin, cos]]
+    }
+
+    #[inline]
+    pub fn world_point(&self, local: [f64; 2]) -> [f64; 2] {
+        let [u, v] = self.axes();
+        [
+            self.centre[0] + local[0] * u[0] + local[1] * v[0],
+            self.centre[1] + local[0] * u[1] + local[1] * v[1],
+        ]
+    }
+
+    pub fn corners(&self) -> [[f64; 2]; 4] {
+        let [w, h] = [self.size[0] / 2.0, self.size[1] / 2.0];
+        [
+            self.world_point([-w, -h]),
+            self.world_point([w, -h]),
+            self.world_point([w, h]),
+            self.world_point([-w, h]),
+        ]
+    }
+
+    /// Separating axis test against another oriented rectangle
+    pub fn intersect(&self, other: &OrientedRectangle) -> bool {
+        self.separation(other).is_some()
+    }
+
+    /// Shortest translation that moves this rectangle clear of `other`, if they overlap
+    pub fn separation(&self, other: &OrientedRectangle) -> Option<[f64; 2]> {
+        let corners = [self.corners(), other.corners()];
+        let mut best: Option<([f64; 2], f64)> = None;
+        for axis in self.axes().iter().chain(other.axes().iter()) {
+            let [a, b] = [0, 1].map(|i| {
+                corners[i].iter()
+                    .map(|c| c[0] * axis[0] + c[1] * axis[1])
+                    .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), x| (lo.min(x), hi.max(x)))
+            });
+            let overlap = (a.1 - b.0).min(b.1 - a.0);
+            if overlap <= 0.0 {
+                return None;
+            }
+            if best.is_none_or(|(_, o)| overlap < o) {
+                best = Some((*axis, overlap));
+            }
+        }
+        best.map(|(axis, overlap)| {
+            // Push away from the other rectangle's centre
+            let d = [self.centre[0] - other.centre[0], self.centre[1] - other.centre[1]];
+            let sign = if d[0] * axis[0] + d[1] * axis[1] < 0.0 { -1.0 } else { 1.0 };
+            [sign * axis[0] * overlap, sign * axis[1] * overlap]
+        })
+    }
+
+    /// Axis-aligned rectangle enclosing this one
+    pub fn bounds(&self) -> Rectangle {
+        let corners = self.corners();
+        let xmin = corners.iter().map(|c| c[0]).fold(f64::INFINITY, f64::min);
+        let xmax = corners.iter().map(|c| c[0]).fold(f64::NEG_INFINITY, f64::max);
+        let ymin = corners.iter().map(|c| c[1]).fold(f64::INFINITY, f64::min);
+        let ymax = corners.iter().map(|c| c[1]).fold(f64::NEG_INFINITY, f64::max);
+        Rectangle::new([xmin, ymin], [xmax - xmin, ymax - ymin])
+    }
+
+    /// Grid of `n[0]` by `n[1]` points spread evenly over the rectangle
+    pub fn sample_points(&self, n: [usize; 2]) -> Vec<[f64; 2]> {
+        (0..n[0]).flat_map(|i| (0..n[1]).map(move |j| (i, j)))
+            .map(|(i, j)| self.world_point([
+                ((i as f64 + 0.5) / n[0] as f64 - 0.5) * self.size[0],
+                ((j as f64 + 0.5) / n[1] as f64 - 0.5) * self.size[1],
+            ]))
+            .collect()
+    }
+}
+
+impl From<Rectangle> for OrientedRectangle {
+    fn from(rect: Rectangle) -> OrientedRectangle {
+        OrientedRectangle::new(rect.centre(), [rect.0[2], rect.0[3]], 0.0)
+    }
+}
+
+/// Precise outline of an entity, used for picking it up with the mouse
+#[derive(Clone, Debug)]
+pub enum Shape {
+    Rectangle(Rectangle),
+    Ellipse(Rectangle),
+    Circle([f64; 2], f64),
+    RoundedRectangle(Rectangle, f64),
+    Polygon(Vec<[f64; 2]>),
+    /// Stroke of a circular arc, with the same conventions as `piston_window::circle_arc`
+    Arc {
+        centre: [f64; 2],
+        radius: f64,
+        start: f64,
+        end: f64,
+        thickness: f64,
+    },
+    Union(Vec<Shape>),
+    /// Another shape rotated by `angle` radians about `centre`
+    Rotated(Box<Shape>, [f64; 2], f64),
+}
+
+impl Shape {
+    pub fn contains(&self, point: [f64; 2]) -> bool {
+        match self {
+            Shape::Rectangle(rect) => rect.intersect_point(point),
+            Shape::Ellipse(rect) => {
+                let centre = rect.centre();
+                let x = 2.0 * (point[0] - centre[0]) / rect.0[2];
+                let y = 2.0 * (point[1] - centre[1]) / rect.0[3];
+                x * x + y * y < 1.0
+            },
+            Shape::Circle(centre, radius) => {
+                (point[0] - centre[0]).powi(2) + (point[1] - centre[1]).powi(2) < radius * radius
+            },
+            Shape::RoundedRectangle(rect, radius) => {
+                if !rect.intersect_point(point) {
+                    return false;
+                }
+                // Clamp to the rectangle inset by the radius, then check the distance to it
+                let [x, y, w, h] = rect.0;
+                let nearest = [
+                    point[0].max(x + radius).min(x + w - radius),
+                    point[1].max(y + radius).min(y + h - radius),
+                ];
+                (point[0] - nearest[0]).powi(2) + (point[1] - nearest[1]).powi(2) < radius * radius
+            },
+            Shape::Polygon(vertices) => {
+                // Even-odd rule: count the edges crossed by a ray heading right from the point
+                let mut inside = false;
+                for (i, a) in vertices.iter().enumerate() {
+                    let b = vertices[(i + 1) % vertices.len()];
+                    if (a[1] > point[1]) != (b[1] > point[1])
+                        && point[0] < a[0] + (point[1] - a[1]) / (b[1] - a[1]) * (b[0] - a[0]) {
+                        inside = !inside;
+                    }
+                }
+                inside
+            },
+            Shape::Arc{centre, radius, start, end, thickness} => {
+                let dx = point[0] - centre[0];
+                let dy = point[1] - centre[1];
+                if ((dx * dx + dy * dy).sqrt() - radius).abs() > *thickness {
+                    return false;
+                }
+                let tau = 2.0 * std::f64::consts::PI;
+                if end - start >= tau {
+                    return true;
+                }
+                let angle = (dy.atan2(dx) - start).rem_euclid(tau);
+                angle <= (end - start).rem_euclid(tau)
+            },
+            Shape::Union(shapes) => shapes.iter().any(|s| s.contains(point)),
+            Shape::Rotated(shape, centre, angle) => {
+                let (sin, cos) = angle.sin_cos();
+                let d = [point[0] - centre[0], point[1] - centre[1]];
+                shape.contains([
+                    centre[0] + d[0] * cos + d[1] * sin,
+                    centre[1] - d[0] * sin + d[1] * cos,
+                ])
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::f64::consts::PI;
+
+    #[test]
+    fn rectangle_contains() {
+        let shape = Shape::Rectangle(Rectangle::new([10.0, 20.0], [30.0, 40.0]));
+        assert!(shape.contains([25.0, 40.0]));
+        assert!(!shape.contains([5.0, 40.0]));
+        assert!(!shape.contains([25.0, 61.0]));
+    }
+
+    #[test]
+    fn ellipse_contains() {
+        let shape = Shape::Ellipse(Rectangle::centered([0.0, 0.0], [40.0, 20.0]));
+        assert!(shape.contains([19.0, 0.0]));
+        assert!(shape.contains([0.0, 9.0]));
+        // Inside the bounding box, but past the curve
+        assert!(!shape.contains([15.0, 8.0]));
+        assert!(!shape.contains([0.0, 11.0]));
+    }
+
+    #[test]
+    fn circle_contains() {
+        let shape = Shape::Circle([5.0, 5.0], 10.0);
+        assert!(shape.contains([5.0, 14.0]));
+        assert!(shape.contains([12.0, 12.0]));
+        assert!(!shape.contains([13.0, 13.0]));
+    }
+
+    #[test]
+    fn rounded_rectangle_contains() {
+        let shape = Shape::RoundedRectangle(Rectangle::new([0.0, 0.0], [40.0, 20.0]), 5.0);
+        assert!(shape.contains([20.0, 10.0]));
+        assert!(shape.contains([1.0, 10.0]));
+        // The very corner is cut off, but the rest of the rounded corner isn't
+        assert!(!shape.contains([0.5, 0.5]));
+        assert!(shape.contains([2.0, 2.0]));
+        assert!(!shape.contains([41.0, 10.0]));
+    }
+
+    #[test]
+    fn polygon_contains() {
+        // An L shape, to check a concave corner
+        let shape = Shape::Polygon(vec![[0.0, 0.0], [20.0, 0.0], [20.0, 10.0], [10.0, 10.0], [10.0, 20.0], [0.0, 20.0]]);
+        assert!(shape.contains([5.0, 5.0]));
+        assert!(shape.contains([15.0, 5.0]));
+        assert!(shape.contains([5.0, 15.0]));
+        assert!(!shape.contains([15.0, 15.0]));
+        assert!(!shape.contains([-1.0, 5.0]));
+    }
+
+    #[test]
+    fn arc_contains() {
+        let shape = Shape::Arc{centre: [0.0, 0.0], radius: 10.0, start: 0.0, end: PI / 2.0, thickness: 2.0};
+        let on = |angle: f64, r: f64| [r * angle.cos(), r * angle.sin()];
+        assert!(shape.contains(on(PI / 4.0, 10.0)));
+        assert!(shape.contains(on(PI / 4.0, 11.5)));
+        assert!(!shape.contains(on(PI / 4.0, 13.0)));
+        assert!(!shape.contains([0.0, 0.0]));
+        // On the circle, but outside the sweep of the arc
+        assert!(!shape.contains(on(PI, 10.0)));
+        assert!(!shape.contains(on(-PI / 4.0, 10.0)));
+    }
+
+    #[test]
+    fn arc_wrapping_past_a_full_turn() {
+        let shape = Shape::Arc{centre: [0.0, 0.0], radius: 10.0, start: 1.5 * PI, end: 2.5 * PI, thickness: 2.0};
+        assert!(shape.contains([10.0, 0.0]));
+        assert!(!shape.contains([-10.0, 0.0]));
+        let ring = Shape::Arc{centre: [0.0, 0.0], radius: 10.0, start: 0.0, end: 2.0 * PI, thickness: 2.0};
+        assert!(ring.contains([-10.0, 0.0]));
+    }
+
+    #[test]
+    fn union_contains() {
+        let shape = Shape::Union(vec![
+            Shape::Circle([0.0, 0.0], 5.0),
+            Shape::Rectangle(Rectangle::new([20.0, 0.0], [10.0, 10.0])),
+        ]);
+        assert!(shape.contains([1.0, 1.0]));
+        assert!(shape.contains([25.0, 5.0]));
+        assert!(!shape.contains([12.0, 0.0]));
+        assert!(!Shape::Union(vec![]).contains([0.0, 0.0]));
+    }
+
+    #[test]
+    fn rotated_contains() {
+        // A long thin rectangle along the x axis, turned a quarter turn to lie along the y axis
+        let rect = Shape::Rectangle(Rectangle::centered([0.0, 0.0], [40.0, 4.0]));
+        let shape = Shape::Rotated(Box::new(rect), [0.0, 0.0], PI / 2.0);
+        assert!(shape.contains([0.0, 15.0]));
+        assert!(shape.contains([0.0, -15.0]));
+        assert!(!shape.contains([15.0, 0.0]));
+
+        let diagonal = Shape::Rotated(Box::new(Shape::Rectangle(Rectangle::centered([10.0, 10.0], [40.0, 4.0]))), [10.0, 10.0], PI / 4.0);
+        assert!(diagonal.contains([20.0, 20.0]));
+        assert!(!diagonal.contains([20.0, 0.0]));
+        assert!(!diagonal.contains([28.0, 10.0]));
+    }
+
+    fn moved(rect: &OrientedRectangle, by: [f64; 2]) -> OrientedRectangle {
+        OrientedRectangle::new([rect.centre[0] + by[0], rect.centre[1] + by[1]], rect.size, rect.angle)
+    }
+
+    #[test]
+    fn touching_rectangles_do_not_intersect() {
+        let a = OrientedRectangle::new([0.0, 0.0], [10.0, 10.0], 0.0);
+        let b = OrientedRectangle::new([10.0, 0.0], [10.0, 10.0], 0.0);
+        assert!(!a.intersect(&b));
+        assert!(a.separation(&b).is_none());
+    }
+
+    #[test]
+    fn overlapping_rectangles_are_pushed_apart() {
+        let a = OrientedRectangle::new([0.0, 0.0], [10.0, 10.0], 0.0);
+        let b = OrientedRectangle::new([8.0, 1.0], [10.0, 10.0], 0.0);
+        assert!(a.intersect(&b));
+        // Out along the shallowest overlap, away from the other rectangle
+        let push = a.separation(&b).unwrap();
+        assert!((push[0] + 2.0).abs() < 1e-9 && push[1].abs() < 1e-9);
+        let push = b.separation(&a).unwrap();
+        assert!((push[0] - 2.0).abs() < 1e-9 && push[1].abs() < 1e-9);
+        assert!(!moved(&a, a.separation(&b).unwrap()).intersect(&b));
+    }
+
+    #[test]
+    fn rotated_rectangles_apart_despite_overlapping_bounds() {
+        let a = OrientedRectangle::new([0.0, 0.0], [40.0, 4.0], PI / 4.0);
+        let b = OrientedRectangle::new([10.0, -10.0], [4.0, 4.0], 0.0);
+        assert!(a.bounds().intersect_rect(&b.bounds()));
+        assert!(!a.intersect(&b));
+        assert!(!b.intersect(&a));
+        // Slide it over onto the diagonal and they do meet
+        assert!(moved(&b, [-8.0, 8.0]).intersect(&a));
+    }
+
+    #[test]
+    fn rotated_separation_points_away() {
+        let a = OrientedRectangle::new([0.0, 0.0], [40.0, 4.0], PI / 4.0);
+        let b = OrientedRectangle::new([3.0, -1.0], [4.0, 4.0], 0.0);
+        let push = b.separation(&a).unwrap();
+        // Across the diagonal, on the side b is already on
+        assert!(push[0] > 0.0 && push[1] < 0.0);
+        assert!(!moved(&b, [push[0] * 1.001, push[1] * 1.001]).intersect(&a));
+    }
+
+    #[test]
+    fn contained_rectangle_intersects() {
+        let outer = OrientedRectangle::new([0.0, 0.0], [40.0, 40.0], 0.3);
+        let inner = OrientedRectangle::new([1.0, 2.0], [4.0, 4.0], 0.0);
+        assert!(outer.intersect(&inner));
+        assert!(inner.intersect(&outer));
+        // Pushed far enough to clear the whole of the outer rectangle
+        let push = inner.separation(&outer).unwrap();
+        assert!(!moved(&inner, [push[0] * 1.001, push[1] * 1.001]).intersect(&outer));
+    }
+
+    #[test]
+    fn sample_points_cover_the_rectangle() {
+        let rect = OrientedRectangle::new([10.0, 20.0], [30.0, 10.0], 0.0);
+        let points = rect.sample_points([3, 5]);
+        assert_eq!(points.len(), 15);
+        assert!(points.iter().all(|p| rect.bounds().intersect_point(*p)));
+        assert!(points.iter().any(|p| p[0].abs() < 1e-9 && (p[1] - 16.0).abs() < 1e-9));
+        let mean = points.iter().fold([0.0, 0.0], |m, p| [m[0] + p[0] / 15.0, m[1] + p[1] / 15.0]);
+        assert!((mean[0] - 10.0).abs() < 1e-9 && (mean[1] - 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn sample_points_follow_rotation() {
+        let rect = OrientedRectangle::new([0.0, 0.0], [40.0, 4.0], PI / 2.0);
+        let shape = Shape::Rotated(Box::new(Shape::Rectangle(Rectangle::centered([0.0, 0.0], [40.0, 4.0]))), [0.0, 0.0], PI / 2.0);
+        for point in rect.sample_points([4, 2]) {
+            assert!(point[0].abs() < 2.0 && point[1].abs() < 20.0);
+            assert!(shape.contains(point));
+        }
+    }
+}
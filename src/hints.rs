@@ -0,0 +1,114 @@
+use piston_window::context::Context;
+
+use crate::entity::{G, Entity, Clock, Pointer, Topping};
+use crate::geometry::Rectangle;
+use crate::locale::Locale;
+use crate::text::Font;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+const HINT_TIME: f64 = 6.0;
+
+/// Food put down on the stall, and the last warning seen for it
+struct Watched {
+    entity: Rc<RefCell<dyn Entity>>,
+    warning: Option<&'static str>,
+}
+
+struct Hint {
+    entity: Rc<RefCell<dyn Entity>>,
+    warning: &'static str,
+    /// Where the food was when the hint came up
+    pos: [f64; 2],
+    pointer: Pointer,
+    age: f64,
+}
+
+/// Points out trouble on the stall once the polls are open, like food left burning
+pub struct Hints {
+    clock: Rc<RefCell<Clock>>,
+    locale: Rc<Locale>,
+    watched: Vec<Watched>,
+    current: Option<Hint>,
+}
+
+impl Hints {
+    pub fn new(clock: Rc<RefCell<Clock>>, locale: Rc<Locale>) -> Hints {
+        Hints{
+            clock,
+            locale,
+            watched: vec![],
+            current: None,
+        }
+    }
+
+    fn caption(&self, warning: &str, entity: &dyn Entity) -> String {
+        let name = match entity.topping() {
+            Some(Topping::Filling(filling)) => filling.id(),
+            Some(Topping::Onion) => "onion",
+            _ => "",
+        };
+        self.locale.format(warning, self.locale.get(&format!("item_{}", name)))
+    }
+}
+
+impl Entity for Hints {
+    fn bounds(&self) -> Rectangle {
+        Rectangle::new([0.0, 0.0], [0.0, 0.0])
+    }
+
+    fn update(&mut self, dt: f64) -> Vec<Rc<RefCell<dyn Entity>>> {
+        // Forget about anything nobody else is holding on to any more
+        self.watched.retain(|w| Rc::strong_count(&w.entity) > 1);
+
+        if let Some(hint) = &mut self.current {
+            hint.age += dt;
+            hint.pointer.update(dt);
+            // Once the food has been moved the player has seen to it
+            let moved = hint.entity.borrow().get_pos() != hint.pos;
+            if hint.age > HINT_TIME || hint.entity.borrow().warning() != Some(hint.warning) || moved {
+                self.current = None;
+            }
+        }
+
+        if !self.clock.borrow().open() {
+            return vec![];
+        }
+        // Only point out a warning as it first comes up, so players aren't nagged
+        let mut new = None;
+        for watched in self.watched.iter_mut() {
+            let warning = watched.entity.borrow().warning();
+            if new.is_none() && warning.is_some() && warning != watched.warning {
+                new = warning.map(|w| (watched.entity.clone(), w));
+            }
+            watched.warning = warning;
+        }
+        if let (None, Some((entity, warning))) = (&self.current, new) {
+            let caption = self.caption(warning, &*entity.borrow());
+            let pos = entity.borrow().get_pos();
+            let pointer = Pointer::pulse(pos).with_caption(caption);
+            self.current = Some(Hint{entity, warning, pos, pointer, age: 0.0});
+        }
+        vec![]
+    }
+
+    fn other_dropped(&mut self, other: &Rc<RefCell<dyn Entity>>) {
+        let cookable = matches!(other.borrow().topping(), Some(Topping::Filling(_)) | Some(Topping::Onion));
+        if cookable && !self.watched.iter().any(|w| Rc::ptr_eq(&w.entity, other)) {
+            self.watched.push(Watched{entity: other.clone(), warning: None});
+        }
+    }
+
+    fn draw(&self, context: Context, graphics: &mut G) {
+        if let Some(hint) = &self.current {
+            hint.pointer.draw(context, graphics);
+        }
+    }
+
+    fn draw_text(&self, context: Context, font: &mut Font, graphics: &mut G) {
+        if let Some(hint) = &self.current {
+            hint.pointer.draw_text(context, font, graphics);
+        }
+    }
+}
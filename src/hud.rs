@@ -0,0 +1,132 @@
+use piston_window::context::Context;
+
+use crate::entity::{G, Clock, Mood};
+use crate::geometry::Rectangle;
+use crate::locale::Locale;
+use crate::text::{Align, Font};
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+const PRICE: f64 = 3.0;
+const TIP: f64 = 0.5;
+const TAKINGS_SIZE: f64 = 16.0;
+const FPS_SIZE: f64 = 10.0;
+const MESSAGE_SIZE: f64 = 22.0;
+const MESSAGE_TIME: f64 = 3.0;
+const MESSAGE_FADE: f64 = 0.5;
+const MESSAGE_Y: f64 = 140.0;
+const MESSAGE_SPACING: f64 = 28.0;
+const HUD_PADDING: f64 = 6.0;
+/// Room left at the right end of the ticket rail for the takings
+pub const HUD_WIDTH: f64 = 90.0;
+const WHITE: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const SHADOW: [f32; 4] = [0.0, 0.0, 0.0, 0.5];
+
+struct Message {
+    text: String,
+    age: f64,
+}
+
+/// Everything drawn over the top of the stall: takings, frame rate and passing announcements
+pub struct Hud {
+    view: Rectangle,
+    clock: Rc<RefCell<Clock>>,
+    locale: Rc<Locale>,
+    takings: f64,
+    fps: usize,
+    open: bool,
+    closed: bool,
+    messages: Vec<Message>,
+}
+
+impl Hud {
+    pub fn new(view: Rectangle, clock: Rc<RefCell<Clock>>, locale: Rc<Locale>) -> Hud {
+        Hud{
+            view,
+            clock,
+            locale,
+            takings: 0.0,
+            fps: 0,
+            open: false,
+            closed: false,
+            messages: vec![],
+        }
+    }
+
+    /// Show the message `id` in the middle of the screen for a few seconds
+    pub fn message(&mut self, id: &str) {
+        let text = self.locale.get(id).to_string();
+        self.messages.push(Message{text, age: 0.0});
+    }
+
+    pub fn set_fps(&mut self, fps: usize) {
+        self.fps = fps;
+    }
+
+    /// Take payment for each bread handed over, and announce the polls opening and closing
+    pub fn update(&mut self, dt: f64, served: Vec<Mood>) {
+        for mood in served {
+            match mood {
+                Mood::Happy => self.takings += PRICE + TIP,
+                Mood::Neutral | Mood::Sad => self.takings += PRICE,
+                Mood::Sick => self.message("refund"),
+            }
+        }
+
+        let (open, closed) = {
+            let clock = self.clock.borrow();
+            (clock.open(), clock.closed())
+        };
+        if open && !self.open {
+            self.message("polls_open");
+        }
+        if closed && !self.closed {
+            self.message("polls_closed");
+        }
+        self.open = open;
+        self.closed = closed;
+
+        for message in self.messages.iter_mut() {
+            message.age += dt;
+        }
+        self.messages.retain(|m| m.age < MESSAGE_TIME);
+    }
+
+    pub fn draw(&self, context: Context, font: &mut Font, graphics: &mut G) {
+        let [left, top, width, height] = self.view.as_floats();
+        font.draw(&self.locale.currency(self.takings),
+                  [left + width - HUD_PADDING, top + TAKINGS_SIZE + 3.0],
+                  TAKINGS_SIZE,
+                  WHITE,
+                  Align::Right,
+                  context,
+                  graphics);
+        font.draw(&self.locale.format("fps", &self.fps.to_string()),
+                  [left + HUD_PADDING, top + height - HUD_PADDING],
+                  FPS_SIZE,
+                  WHITE,
+                  Align::Left,
+                  context,
+                  graphics);
+
+        for (i, message) in self.messages.iter().enumerate() {
+            let alpha = ((MESSAGE_TIME - message.age) / MESSAGE_FADE).min(1.0) as f32;
+            let pos = [left + width / 2.0, top + MESSAGE_Y + i as f64 * MESSAGE_SPACING];
+            font.draw(&message.text,
+                      [pos[0] + 1.5, pos[1] + 1.5],
+                      MESSAGE_SIZE,
+                      [SHADOW[0], SHADOW[1], SHADOW[2], SHADOW[3] * alpha],
+                      Align::Centre,
+                      context,
+                      graphics);
+            font.draw(&message.text,
+                      pos,
+                      MESSAGE_SIZE,
+                      [WHITE[0], WHITE[1], WHITE[2], alpha],
+                      Align::Centre,
+                      context,
+                      graphics);
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use serde::Deserialize;
+
+use std::collections::BTreeMap;
+
+/// Every shipped locale, by the code passed to `--locale`
+const LOCALES: [(&str, &str); 3] = [
+    ("en", include_str!("../assets/locales/en.toml")),
+    ("fr", include_str!("../assets/locales/fr.toml")),
+    ("de", include_str!("../assets/locales/de.toml")),
+];
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// How numbers are written
+#[derive(Deserialize)]
+struct NumberFormat {
+    /// Pattern for an amount of money, with `{}` where the number goes
+    currency: String,
+    decimal: String,
+    thousands: String,
+}
+
+/// The player-facing text in one language, keyed by message id
+#[derive(Deserialize)]
+pub struct Locale {
+    pub name: String,
+    format: NumberFormat,
+    strings: BTreeMap<String, String>,
+}
+
+impl Locale {
+    /// Load the locale with code `code`, if we ship it
+    pub fn load(code: &str) -> Option<Locale> {
+        LOCALES.iter()
+            .find(|(c, _)| *c == code)
+            .map(|(_, data)| toml::from_str(data).expect("Invalid locale"))
+    }
+
+    /// The codes of every shipped locale
+    pub fn codes() -> impl Iterator<Item = &'static str> {
+        LOCALES.iter().map(|(c, _)| *c)
+    }
+
+    /// Look up the message `id`, falling back to the id itself so missing text is obvious
+    pub fn get<'a>(&'a self, id: &'a str) -> &'a str {
+        self.lookup(id).unwrap_or(id)
+    }
+
+    /// Look up the message `id`, if there is one
+    pub fn lookup(&self, id: &str) -> Option<&str> {
+        self.strings.get(id).map(|s| s.as_str())
+    }
+
+    /// Look up the message `id` and fill in its `{}` with `arg`
+    pub fn format(&self, id: &str, arg: &str) -> String {
+        self.get(id).replacen("{}", arg, 1)
+    }
+
+    /// Write `value` with `decimals` places and the locale's separators
+    pub fn number(&self, value: f64, decimals: usize) -> String {
+        let digits = format!("{:.*}", decimals, value.abs());
+        let (whole, fraction) = match digits.find('.') {
+            Some(n) => (&digits[..n], &digits[n + 1..]),
+            None => (digits.as_str(), ""),
+        };
+        let mut grouped = String::new();
+        for (i, digit) in whole.chars().enumerate() {
+            if i > 0 && (whole.len() - i) % 3 == 0 {
+                grouped.push_str(&self.format.thousands);
+            }
+            grouped.push(digit);
+        }
+        let sign = if value < 0.0 && digits.chars().any(|c| c != '0' && c != '.') { "-" } else { "" };
+        if fraction.is_empty() {
+            format!("{}{}", sign, grouped)
+        } else {
+            format!("{}{}{}{}", sign, grouped, self.format.decimal, fraction)
+        }
+    }
+
+    /// Write an amount of money, in dollars and cents
+    pub fn currency(&self, value: f64) -> String {
+        self.format.currency.replacen("{}", &self.number(value, 2), 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_key_in_every_locale() {
+        let default = Locale::load(DEFAULT_LOCALE).unwrap();
+        for code in Locale::codes() {
+            let locale = Locale::load(code).unwrap();
+            for key in default.strings.keys() {
+                assert!(locale.strings.contains_key(key), "{} is missing {}", code, key);
+            }
+            for key in locale.strings.keys() {
+                assert!(default.strings.contains_key(key), "{} has unknown key {}", code, key);
+            }
+        }
+    }
+
+    #[test]
+    fn currency_formatting() {
+        assert_eq!(Locale::load("en").unwrap().currency(1234.5), "$1,234.50");
+        assert_eq!(Locale::load("fr").unwrap().currency(1234.5), "1 234,50 $");
+        assert_eq!(Locale::load("de").unwrap().currency(3.0), "3,00 $");
+    }
+}
@@ -5,89 +5,229 @@ use std::cell::RefCell;
 use std::rc::Rc;
 use std::time::Instant;
 
+mod arrivals;
 mod colour;
 mod entity;
 mod geometry;
+mod hints;
+mod hud;
+mod locale;
+mod menu;
+mod progress;
 mod scene;
+mod settings;
+mod storage;
+mod text;
+mod tutorial;
+mod viewport;
 
 use entity::Entity;
+use hud::Hud;
+use locale::{Locale, DEFAULT_LOCALE};
+use menu::{Choice, Menu};
+use progress::Progress;
 use scene::Scene;
+use settings::{Field, Settings};
+use text::Font;
+use tutorial::Tutorial;
+use viewport::Viewport;
+
+const ROTATE_STEP: f64 = std::f64::consts::PI / 12.0;
 
 fn main() {
+    let grid = std::env::args().any(|arg| arg == "--grid");
+    let large = std::env::args().any(|arg| arg == "--large");
+    let args: Vec<String> = std::env::args().collect();
+    let code = args.windows(2)
+        .find(|w| w[0] == "--locale")
+        .map(|w| w[1].as_str())
+        .unwrap_or(DEFAULT_LOCALE);
+    let locale = Rc::new(Locale::load(code).unwrap_or_else(|| {
+        let available: Vec<_> = Locale::codes()
+            .map(|c| format!("{} ({})", c, Locale::load(c).unwrap().name))
+            .collect();
+        eprintln!("Unknown locale {}, available: {}", code, available.join(", "));
+        Locale::load(DEFAULT_LOCALE).unwrap()
+    }));
+    let mut settings = Settings::load();
+    let mut progress = Progress::load();
+    let chapters = Tutorial::titles().len();
+    // The layout only changes on restart, so the stall stays the same shape while playing
+    let wide = settings.widescreen;
+    // Carry on the tutorial from the first chapter not yet finished
+    let first = if progress.tutorial_completed { chapters } else { progress.chapters_completed.min(chapters) };
+    let mut run = first..chapters;
+    let mut scene = Scene::new(grid, large, wide, run.clone(), locale.clone(), settings.keys.clone(), settings.palette);
+    let [_, _, view_w, view_h] = scene.view().as_floats();
+    let window_size = |settings: &Settings| settings.window_size.map_or([view_w, view_h], |[w, h]| [w as f64, h as f64]);
+
     let mut window: PistonWindow =
-        WindowSettings::new("Sizzle!", [640, 480])
-        .exit_on_esc(true)
-        //.vsync(true)
-        //.fullscreen(true)
+        WindowSettings::new("Sizzle!", window_size(&settings))
+        .exit_on_esc(false)
+        .vsync(settings.vsync)
+        .fullscreen(settings.fullscreen)
         .build().unwrap();
+    window.set_max_fps(settings.max_fps);
 
+    let mut font = Font::load(&mut window);
+    let mut hud = Hud::new(scene.view(), scene.clock(), locale.clone());
+    let mut menu: Option<Menu> = None;
     let mut fps_counter = FPSCounter::new();
-    let mut fps = 0;
 
     let mut selected: Option<Rc<RefCell<dyn Entity>>> = None;
     let mut last_pos: Option<[f64; 2]> = None;
-    let mut transform: Option<[[f64; 3]; 2]> = None;
-
-    let mut scene = Scene::new();
+    let mut viewport: Option<Viewport> = None;
 
     let mut last_time = Instant::now();
 
     while let Some(e) = window.next() {
-        window.draw_2d(&e, |context, raw_graphics, _device| {
-            let size = context.get_view_size();
-            let sx = size[0] / 640.0;
-            let sy = size[1] / 480.0;
-            let letterbox_h = ((size[0] - 640.0 * sy) / 2.0).max(0.0);
-            let letterbox_v = ((size[1] - 480.0 * sx) / 2.0).max(0.0);
-            let scale = sx.min(sy);
-            let t = math::multiply(
-                math::scale(1.0 / scale, 1.0 / scale),
-                math::translate([-letterbox_h, -letterbox_v]),
-            );
-            let context = context
-                .trans(letterbox_h, letterbox_v)
-                .scale(scale, scale);
-            transform = Some(t);
+        window.draw_2d(&e, |context, raw_graphics, device| {
+            let fitted = Viewport::new(scene.view(), context.get_view_size());
+            let context = fitted.context(context);
+            viewport = Some(fitted);
+            font.set_scale(fitted.scale());
             clear([90.0 / 255.0, 202.0 / 255.0, 77.0 / 255.0, 1.0], raw_graphics);
-            scene.draw(context, raw_graphics);
-            if letterbox_v > 0.0 {
-                piston_window::rectangle([0.0, 0.0, 0.0, 1.0],
-                                         [0.0, -2.0 * letterbox_v, 640.0, 2.0 * letterbox_v],
-                                         context.transform,
-                                         raw_graphics);
-                piston_window::rectangle([0.0, 0.0, 0.0, 1.0],
-                                         [0.0, 480.0, 640.0, 2.0 * letterbox_v],
-                                         context.transform,
-                                         raw_graphics);
+            scene.draw(context, &mut font, raw_graphics);
+            hud.draw(context, &mut font, raw_graphics);
+            if let Some(menu) = &menu {
+                menu.draw(context, &mut font, raw_graphics);
             }
-            if letterbox_h > 0.0 {
+            for bar in fitted.bars() {
                 piston_window::rectangle([0.0, 0.0, 0.0, 1.0],
-                                         [-2.0 * letterbox_h, 0.0, 2.0 * letterbox_h, 480.0],
-                                         context.transform,
-                                         raw_graphics);
-                piston_window::rectangle([0.0, 0.0, 0.0, 1.0],
-                                         [640.0, 0.0, 2.0 * letterbox_h, 480.0],
+                                         bar,
                                          context.transform,
                                          raw_graphics);
             }
-            fps = fps_counter.tick();
+            font.encoder().flush(device);
+            hud.set_fps(fps_counter.tick());
         });
-        window.set_title(fps.to_string());
-        
-        if let Some(_) = e.update_args() {
+
+        if e.update_args().is_some() {
             let time = Instant::now();
             let dt = (time - last_time).as_secs_f64();
             last_time = time;
-            scene.update(dt);
+            if menu.is_none() {
+                scene.update(dt);
+                hud.update(dt, scene.take_served());
 
-            if let Some(ref mut selected) = selected {
-                selected.borrow_mut().update_selected(dt);
+                if let Some(ref mut selected) = selected {
+                    selected.borrow_mut().update_selected(dt);
+                    scene.tool_used(selected);
+                }
+            }
+            if progress.record(&run, chapters, scene.tutorial_chapter(), scene.tutorial_finished()) {
+                progress.save();
             }
         }
 
+        if let Some(Button::Keyboard(key)) = e.press_args() {
+            let listening = menu.as_ref().is_some_and(|m| m.listening());
+            if key == settings.keys.fullscreen && !listening {
+                settings.fullscreen = !settings.fullscreen;
+                set_fullscreen(&window, settings.fullscreen);
+                settings.save();
+                if let Some(ref mut open) = menu {
+                    open.refresh(&locale, &settings);
+                }
+                continue;
+            }
+            if menu.is_none() && key == settings.keys.menu {
+                // Put down anything held before pausing
+                if let Some(ref mut selected) = selected {
+                    selected.borrow_mut().drop();
+                    scene.dropped(selected);
+                }
+                selected = None;
+                menu = Some(Menu::new(scene.view(), &locale));
+                continue;
+            }
+        }
+
+        if let Some(ref mut open) = menu {
+            let pos = match (e.mouse_cursor_args(), viewport) {
+                (Some(pos), Some(viewport)) => Some(viewport.to_scene(pos)),
+                _ => None,
+            };
+            if let Some(pos) = pos {
+                open.hover(pos);
+            }
+            let choice = match e.press_args() {
+                Some(Button::Keyboard(key)) => open.key(key),
+                Some(Button::Mouse(MouseButton::Left)) => match (last_pos, viewport) {
+                    (Some(pos), Some(viewport)) => open.click(viewport.to_scene(pos)),
+                    _ => None,
+                },
+                _ => None,
+            };
+            let restart = match choice {
+                Some(Choice::Resume) => {
+                    menu = None;
+                    None
+                },
+                // Starting again sends everyone home, so check first once the polls have opened
+                Some(choice @ (Choice::Tutorial | Choice::Chapter(_))) if scene.clock().borrow().started() && !open.confirming() => {
+                    *open = Menu::confirm(scene.view(), &locale, choice);
+                    None
+                },
+                Some(Choice::Tutorial) => Some(0..chapters),
+                Some(Choice::Chapter(chapter)) => Some(chapter..chapter + 1),
+                Some(Choice::Settings) => {
+                    *open = Menu::settings(scene.view(), &locale, &settings);
+                    None
+                },
+                Some(Choice::Quit) => {
+                    window.set_should_close(true);
+                    None
+                },
+                Some(Choice::Change(field, forward)) => {
+                    settings.change(field, forward);
+                    match field {
+                        Field::WindowSize => window.set_size(window_size(&settings)),
+                        Field::Fullscreen => set_fullscreen(&window, settings.fullscreen),
+                        Field::MaxFps => window.set_max_fps(settings.max_fps),
+                        Field::Palette => scene.set_palette(settings.palette),
+                        _ => {},
+                    }
+                    settings.save();
+                    open.refresh(&locale, &settings);
+                    None
+                },
+                Some(Choice::Rebind(action)) => {
+                    open.listen(action, &locale);
+                    None
+                },
+                Some(Choice::Bind(action, key)) => {
+                    settings.keys.set(action, key);
+                    settings.save();
+                    scene.set_keys(settings.keys.clone());
+                    open.refresh(&locale, &settings);
+                    None
+                },
+                Some(Choice::Back) => {
+                    *open = Menu::new(scene.view(), &locale);
+                    None
+                },
+                None => None,
+            };
+            if let Some(chapters) = restart {
+                run = chapters;
+                scene = Scene::new(grid, large, wide, run.clone(), locale.clone(), settings.keys.clone(), settings.palette);
+                hud = Hud::new(scene.view(), scene.clock(), locale.clone());
+                menu = None;
+            }
+            if let Some(pos) = e.mouse_cursor_args() {
+                last_pos = Some(pos);
+            }
+            continue;
+        }
+
+        if e.press_args() == Some(Button::Keyboard(settings.keys.skip_tutorial)) {
+            scene.skip_tutorial();
+        }
+
         if let Some(button) = e.press_args() {
-            if let (Some(pos), Some(transform)) = (last_pos, transform) {
-                let pos = math::transform_pos(transform, pos);
+            if let (Some(pos), Some(viewport)) = (last_pos, viewport) {
+                let pos = viewport.to_scene(pos);
                 if button == Button::Mouse(MouseButton::Left) {
                     selected = scene.select(pos);
                     if let Some(ref mut selected) = selected {
@@ -98,6 +238,25 @@ fn main() {
             }
         }
 
+        if let Some(ref mut selected) = selected {
+            if let Some(scroll) = e.mouse_scroll_args() {
+                selected.borrow_mut().rotate(scroll[1] * ROTATE_STEP);
+            }
+            let keys = &settings.keys;
+            match e.press_args() {
+                Some(Button::Keyboard(key)) if key == keys.rotate_left => selected.borrow_mut().rotate(-ROTATE_STEP),
+                Some(Button::Keyboard(key)) if key == keys.rotate_right => selected.borrow_mut().rotate(ROTATE_STEP),
+                Some(Button::Keyboard(key)) if key == keys.squeeze => selected.borrow_mut().squeeze(true),
+                Some(Button::Mouse(MouseButton::Right)) => selected.borrow_mut().squeeze(true),
+                _ => {},
+            }
+            match e.release_args() {
+                Some(Button::Keyboard(key)) if key == keys.squeeze => selected.borrow_mut().squeeze(false),
+                Some(Button::Mouse(MouseButton::Right)) => selected.borrow_mut().squeeze(false),
+                _ => {},
+            }
+        }
+
         if let Some(button) = e.release_args() {
             if button == Button::Mouse(MouseButton::Left) {
                 if let Some(ref mut selected) = selected {
@@ -109,10 +268,11 @@ fn main() {
         }
 
         if let Some(ref mut selected) = selected {
-            if let (Some(pos), Some(last_pos), Some(transform)) = (e.mouse_cursor_args(), last_pos, transform) {
-                let pos = math::transform_pos(transform, pos);
-                let last_pos = math::transform_pos(transform, last_pos);
+            if let (Some(pos), Some(last_pos), Some(viewport)) = (e.mouse_cursor_args(), last_pos, viewport) {
+                let pos = viewport.to_scene(pos);
+                let last_pos = viewport.to_scene(last_pos);
                 selected.borrow_mut().drag(last_pos, pos);
+                scene.tool_used(selected);
             }
         }
 
@@ -120,4 +280,11 @@ fn main() {
             last_pos = Some(pos);
         }
     }
-}
\ No newline at end of file
+}
+
+/// Switch the window in or out of fullscreen on whichever monitor it's on
+fn set_fullscreen(window: &PistonWindow, fullscreen: bool) {
+    let glutin_window = window.window.ctx.window();
+    let monitor = if fullscreen { Some(glutin_window.get_current_monitor()) } else { None };
+    glutin_window.set_fullscreen(monitor);
+}
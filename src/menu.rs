@@ -0,0 +1,219 @@
+use piston_window::{context::Context, Key};
+
+use crate::entity::G;
+use crate::geometry::Rectangle;
+use crate::locale::Locale;
+use crate::settings::{Action, Field, Keys, Settings};
+use crate::text::{Align, Font};
+use crate::tutorial::Tutorial;
+
+const ITEM_SIZE: f64 = 18.0;
+const ITEM_SPACING: f64 = 32.0;
+const ITEM_WIDTH: f64 = 320.0;
+const SETTING_SIZE: f64 = 15.0;
+const SETTING_SPACING: f64 = 26.0;
+const SETTING_WIDTH: f64 = 440.0;
+const ITEM_PADDING: f64 = 12.0;
+const SHADE: [f32; 4] = [0.0, 0.0, 0.0, 0.7];
+const HIGHLIGHT: [f32; 4] = [1.0, 1.0, 1.0, 0.2];
+const WHITE: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Choice {
+    Resume,
+    /// Start again from the beginning of the tutorial
+    Tutorial,
+    /// Start again with just one chapter of the tutorial
+    Chapter(usize),
+    Settings,
+    Quit,
+    /// Step a setting on to its next value, or back to its previous one
+    Change(Field, bool),
+    /// Wait for the next key to bind to an action
+    Rebind(Action),
+    Bind(Action, Key),
+    /// Go back from the settings, or from starting again, to the pause menu
+    Back,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Page {
+    Pause,
+    Settings,
+    /// Making sure the player means to end the day and start again
+    Confirm,
+}
+
+struct Item {
+    choice: Choice,
+    label: String,
+    /// Shown on the right, for settings
+    value: Option<String>,
+}
+
+impl Item {
+    fn new(choice: Choice, label: String) -> Item {
+        Item{choice, label, value: None}
+    }
+
+    fn with_value(self, value: String) -> Item {
+        Item{value: Some(value), ..self}
+    }
+}
+
+/// The pause menu, or its settings page, shown over the stall
+pub struct Menu {
+    view: Rectangle,
+    items: Vec<Item>,
+    selected: usize,
+    size: f64,
+    spacing: f64,
+    width: f64,
+    page: Page,
+    /// The action waiting for a key to be pressed to bind to it
+    listening: Option<Action>,
+}
+
+impl Menu {
+    pub fn new(view: Rectangle, locale: &Locale) -> Menu {
+        let mut items = vec![
+            Item::new(Choice::Resume, locale.get("menu_resume").to_string()),
+            Item::new(Choice::Tutorial, locale.get("menu_tutorial").to_string()),
+        ];
+        items.extend(Tutorial::titles().iter().enumerate().map(|(i, title)| {
+            Item::new(Choice::Chapter(i), format!("{} {}: {}", locale.get("menu_chapter"), i + 1, locale.get(title)))
+        }));
+        items.push(Item::new(Choice::Settings, locale.get("menu_settings").to_string()));
+        items.push(Item::new(Choice::Quit, locale.get("menu_quit").to_string()));
+        Menu{view, items, selected: 0, size: ITEM_SIZE, spacing: ITEM_SPACING, width: ITEM_WIDTH, page: Page::Pause, listening: None}
+    }
+
+    /// The settings page, with each option and key binding
+    pub fn settings(view: Rectangle, locale: &Locale, settings: &Settings) -> Menu {
+        let mut menu = Menu{
+            view,
+            items: vec![],
+            selected: 0,
+            size: SETTING_SIZE,
+            spacing: SETTING_SPACING,
+            width: SETTING_WIDTH,
+            page: Page::Settings,
+            listening: None,
+        };
+        menu.refresh(locale, settings);
+        menu
+    }
+
+    /// Ask before going ahead with `choice`, which ends the day to start the tutorial again
+    pub fn confirm(view: Rectangle, locale: &Locale, choice: Choice) -> Menu {
+        let items = vec![
+            Item::new(choice, locale.get("menu_confirm_restart").to_string()),
+            Item::new(Choice::Back, locale.get("menu_keep_playing").to_string()),
+        ];
+        Menu{view, items, selected: 1, size: ITEM_SIZE, spacing: ITEM_SPACING, width: ITEM_WIDTH, page: Page::Confirm, listening: None}
+    }
+
+    /// Whether this is asking before starting again
+    pub fn confirming(&self) -> bool {
+        self.page == Page::Confirm
+    }
+
+    /// Show the settings as they are now, after one has changed
+    pub fn refresh(&mut self, locale: &Locale, settings: &Settings) {
+        if self.page != Page::Settings {
+            return;
+        }
+        self.listening = None;
+        self.items = Field::ALL.iter().map(|field| {
+            let label = if field.needs_restart() {
+                format!("{} {}", locale.get(field.name()), locale.get("setting_restart"))
+            } else {
+                locale.get(field.name()).to_string()
+            };
+            Item::new(Choice::Change(*field, true), label).with_value(settings.value(*field, locale))
+        }).collect();
+        self.items.extend(Action::ALL.iter().map(|action| {
+            Item::new(Choice::Rebind(*action), locale.get(action.name()).to_string())
+                .with_value(Keys::name(settings.keys.get(*action), locale))
+        }));
+        self.items.push(Item::new(Choice::Back, locale.get("menu_back").to_string()));
+    }
+
+    /// Wait for the next key press to bind to `action`
+    pub fn listen(&mut self, action: Action, locale: &Locale) {
+        self.listening = Some(action);
+        if let Some(item) = self.items.iter_mut().find(|item| item.choice == Choice::Rebind(action)) {
+            item.value = Some(locale.get("setting_press_key").to_string());
+        }
+    }
+
+    /// Whether the next key press is going to be bound to an action
+    pub fn listening(&self) -> bool {
+        self.listening.is_some()
+    }
+
+    fn item_bounds(&self, i: usize) -> Rectangle {
+        let top = self.view.centre()[1] - self.items.len() as f64 * self.spacing / 2.0;
+        Rectangle::new([self.view.centre()[0] - self.width / 2.0, top + i as f64 * self.spacing], [self.width, self.spacing])
+    }
+
+    fn item_at(&self, pos: [f64; 2]) -> Option<usize> {
+        (0..self.items.len()).find(|&i| self.item_bounds(i).intersect_point(pos))
+    }
+
+    /// Move the highlight with the arrow keys, pick the highlighted item with enter, and change settings
+    /// with left and right
+    pub fn key(&mut self, key: Key) -> Option<Choice> {
+        if let Some(action) = self.listening.take() {
+            return Some(Choice::Bind(action, key));
+        }
+        let choice = self.items[self.selected].choice;
+        match (key, choice) {
+            (Key::Up, _) => self.selected = (self.selected + self.items.len() - 1) % self.items.len(),
+            (Key::Down, _) => self.selected = (self.selected + 1) % self.items.len(),
+            (Key::Left, Choice::Change(field, _)) => return Some(Choice::Change(field, false)),
+            (Key::Right, Choice::Change(field, _)) => return Some(Choice::Change(field, true)),
+            (Key::Return, _) => return Some(choice),
+            (Key::Escape, _) if self.page != Page::Pause => return Some(Choice::Back),
+            (Key::Escape, _) => return Some(Choice::Resume),
+            _ => {},
+        }
+        None
+    }
+
+    pub fn hover(&mut self, pos: [f64; 2]) {
+        if let Some(i) = self.item_at(pos) {
+            self.selected = i;
+        }
+    }
+
+    pub fn click(&mut self, pos: [f64; 2]) -> Option<Choice> {
+        if self.listening.is_some() {
+            return None;
+        }
+        self.item_at(pos).map(|i| self.items[i].choice)
+    }
+
+    pub fn draw(&self, context: Context, font: &mut Font, graphics: &mut G) {
+        piston_window::rectangle(SHADE,
+                                 self.view.as_floats(),
+                                 context.transform,
+                                 graphics);
+        piston_window::rectangle(HIGHLIGHT,
+                                 self.item_bounds(self.selected).as_floats(),
+                                 context.transform,
+                                 graphics);
+        for (i, item) in self.items.iter().enumerate() {
+            let bounds = self.item_bounds(i);
+            let baseline = bounds.centre()[1] + self.size * 0.35;
+            match &item.value {
+                Some(value) => {
+                    let [left, _, width, _] = bounds.as_floats();
+                    font.draw(&item.label, [left + ITEM_PADDING, baseline], self.size, WHITE, Align::Left, context, graphics);
+                    font.draw(value, [left + width - ITEM_PADDING, baseline], self.size, WHITE, Align::Right, context, graphics);
+                },
+                None => font.draw(&item.label, [bounds.centre()[0], baseline], self.size, WHITE, Align::Centre, context, graphics),
+            }
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+use crate::storage;
+
+use std::ops::Range;
+
+const FILE: &str = "progress.toml";
+
+/// How far the player has got, kept between runs
+#[derive(Default, Deserialize, Serialize)]
+pub struct Progress {
+    #[serde(default)]
+    pub tutorial_completed: bool,
+    /// How many chapters of the tutorial the player has got through, to carry on from next time
+    #[serde(default)]
+    pub chapters_completed: usize,
+}
+
+impl Progress {
+    pub fn load() -> Progress {
+        storage::load(FILE)
+    }
+
+    pub fn save(&self) {
+        storage::save(FILE, self);
+    }
+
+    /// Note how far a run through `run` of the tutorial's `total` chapters has got, returning whether that
+    /// changed anything. Only a run carrying on from where the player got to counts, not replaying a chapter
+    pub fn record(&mut self, run: &Range<usize>, total: usize, chapter: usize, finished: bool) -> bool {
+        if self.tutorial_completed || run.start > self.chapters_completed || run.end < total {
+            false
+        } else if finished {
+            self.tutorial_completed = true;
+            true
+        } else if chapter > self.chapters_completed {
+            self.chapters_completed = chapter;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn carrying_on_records_each_chapter() {
+        let mut progress = Progress{tutorial_completed: false, chapters_completed: 1};
+        assert!(!progress.record(&(1..4), 4, 1, false));
+        assert!(progress.record(&(1..4), 4, 2, false));
+        assert_eq!(progress.chapters_completed, 2);
+        assert!(progress.record(&(1..4), 4, 3, true));
+        assert!(progress.tutorial_completed);
+    }
+
+    #[test]
+    fn replaying_a_chapter_leaves_progress_alone() {
+        let mut progress = Progress{tutorial_completed: false, chapters_completed: 1};
+        assert!(!progress.record(&(2..3), 4, 3, true));
+        assert!(!progress.record(&(0..1), 4, 1, true));
+        assert!(!progress.tutorial_completed);
+        assert_eq!(progress.chapters_completed, 1);
+    }
+}
@@ -1,43 +1,181 @@
 use piston_window::{context::Context};
 
-use crate::entity::{G, Entity, Selection, Loaf, SausageTray, PattyTray, Hotplate, Table, Bottle, Condiment, ChoppingBoard, Queue};
+use crate::entity::{G, Entity, Mood, Selection, Loaf, Tray, Filling, Hotplate, WarmingTray, Table, Bottle, Tub, Condiment, Dispenser, ChoppingBoard, Spatula, Clock, Queue, TicketRail};
+use crate::arrivals::{Arrivals, Day};
+use crate::geometry::Rectangle;
+use crate::hints::Hints;
+use crate::hud::HUD_WIDTH;
+use crate::locale::Locale;
+use crate::colour::Palette;
+use crate::settings::Keys;
+use crate::text::Font;
+use crate::tutorial::{Item, Staple, Tutorial};
 
 use std::cell::RefCell;
+use std::ops::Range;
 use std::rc::Rc;
 
-pub struct Scene(Vec<Rc<RefCell<dyn Entity>>>);
+const HEAT_SAMPLES: [usize; 2] = [3, 5];
+const SETTLE_ITERATIONS: usize = 8;
+const VOLUNTEER_SPACING: f64 = 170.0;
+const CAPTION_POS: [f64; 2] = [410.0, 184.0];
+const ONIONS_PER_CHOP: usize = 4;
+// Widths giving a 16:9 view, for the regular and large stalls
+const WIDE_WIDTH: f64 = 854.0;
+const WIDE_LARGE_WIDTH: f64 = 1173.0;
+
+pub struct Scene {
+    entities: Vec<Rc<RefCell<dyn Entity>>>,
+    view: Rectangle,
+    clock: Rc<RefCell<Clock>>,
+    tutorial: Rc<RefCell<Tutorial>>,
+    queues: Vec<Rc<RefCell<Queue>>>,
+    served: Vec<Mood>,
+}
 
 impl Scene {
-    pub fn new() -> Scene {
-        let hotplates: Vec<Rc<RefCell<dyn Entity>>> = vec![
-            Rc::new(RefCell::new(Hotplate::new([200.0, 200.0], [420.0, 200.0], rand::random()))),
+    /// Lay out the stall, with a snaking queue and more room around it if `large` and a longer queue to
+    /// fill a 16:9 screen if `wide`, and play through `chapters` of the tutorial before opening the polls,
+    /// naming `keys` in its captions and showing how customers feel in `palette`
+    pub fn new(grid: bool, large: bool, wide: bool, chapters: Range<usize>, locale: Rc<Locale>, keys: Keys, palette: Palette) -> Scene {
+        let hotplate = Hotplate::new([200.0, 200.0], [420.0, 200.0], rand::random());
+        let hotplate: Rc<RefCell<dyn Entity>> = Rc::new(RefCell::new(if grid { hotplate.with_grid() } else { hotplate }));
+        let board: Rc<RefCell<dyn Entity>> = Rc::new(RefCell::new(ChoppingBoard::new([120.0, 400.0], ONIONS_PER_CHOP)));
+        let warmer: Rc<RefCell<dyn Entity>> = Rc::new(RefCell::new(WarmingTray::new([440.0, 415.0], [140.0, 62.0])));
+        let loaf: Rc<RefCell<dyn Entity>> = Rc::new(RefCell::new(Loaf::new([30.0, 400.0])));
+        let trays = [
+            (Filling::Sausage, [120.0, 270.0]),
+            (Filling::VeggiePatty, [33.0, 282.5]),
+            (Filling::Bacon, [250.0, 447.0]),
+            (Filling::Egg, [330.0, 440.0]),
+            (Filling::Halloumi, [400.0, 449.0]),
         ];
-        Scene(vec![
+        let trays = trays.iter()
+            .map(|(f, pos)| (Item::Filling(*f), Rc::new(RefCell::new(Tray::new(*f, *pos))) as Rc<RefCell<dyn Entity>>));
+        let bottles = Condiment::ALL.iter().filter(|c| c.dispenser() == Dispenser::Bottle).enumerate()
+            .map(|(i, c)| (Item::Condiment(*c), Rc::new(RefCell::new(Bottle::new(*c, [12.0 + 22.0 * i as f64, 180.0]))) as Rc<RefCell<dyn Entity>>));
+        let tubs = Condiment::ALL.iter().filter(|c| c.dispenser() == Dispenser::Tub).enumerate()
+            .map(|(i, c)| (Item::Condiment(*c), Rc::new(RefCell::new(Tub::new(*c, [146.0 + 24.0 * i as f64, 208.0]))) as Rc<RefCell<dyn Entity>>));
+        // What the tutorial refers to by name, and everything else food and condiments come from
+        let fixtures = vec![("board", board.clone()), ("hotplate", hotplate), ("warmer", warmer)];
+        let stock: Vec<(Item, Rc<RefCell<dyn Entity>>)> = std::iter::once((Item::Staple(Staple::Bread), loaf))
+            .chain(trays)
+            .chain(bottles)
+            .chain(tubs)
+            .collect();
+        let sources = std::iter::once((Item::Staple(Staple::Onion), board)).chain(stock.iter().cloned());
+
+        let mut entities: Vec<Rc<RefCell<dyn Entity>>> = vec![
               Rc::new(RefCell::new(Table::new([-40.0, 200.0], [220.0, 440.0]))),
-              Rc::new(RefCell::new(ChoppingBoard::new([120.0, 400.0]))),
-              hotplates[0].clone(),
-              Rc::new(RefCell::new(Loaf::new([30.0, 400.0]))),
-              Rc::new(RefCell::new(SausageTray::new([120.0, 270.0]))),
-              Rc::new(RefCell::new(PattyTray::new([33.0, 282.5]))),
-              Rc::new(RefCell::new(Bottle::new(Condiment::Sauce, [15.0, 180.0]))),
-              Rc::new(RefCell::new(Bottle::new(Condiment::Mustard, [45.0, 180.0]))),
-              Rc::new(RefCell::new(Queue::new([180.0, 50.0], [720.0, 50.0], 4))),
-        ])
-    }
-
-    pub fn draw(&self, context: Context, graphics: &mut G) {
-        for e in self.0.iter() {
-            e.borrow().draw(context, graphics);
+              Rc::new(RefCell::new(Table::new([190.0, 410.0], [480.0, 120.0]))),
+        ];
+        entities.extend(fixtures.iter().map(|(_, e)| e.clone()));
+        entities.extend(stock.iter().map(|(_, e)| e.clone()));
+        entities.push(Rc::new(RefCell::new(Spatula::new([612.0, 425.0]))));
+        let clock = Rc::new(RefCell::new(Clock::new([40.0, 58.0], Day::load())));
+        entities.push(clock.clone());
+        // Voters queue along the front of the stall, while volunteers on their break come down the side.
+        // A wide screen has room for the queue to stretch further along before it turns
+        let (regular, volunteers, view) = if large {
+            let (turn, max_len) = if wide { (1060.0, 12) } else { (660.0, 7) };
+            (Queue::new(vec![[330.0, 64.0], [turn, 64.0], [turn, -106.0], [260.0, -106.0], [260.0, -300.0]],
+                        vec![[330.0, -300.0]],
+                        max_len,
+                        Arrivals::regular(),
+                        clock.clone()),
+             Queue::new(vec![[180.0, 64.0], [180.0, -300.0]],
+                        vec![[-160.0, 64.0]],
+                        3,
+                        Arrivals::volunteers(),
+                        clock.clone()),
+             Rectangle::new([-80.0, -180.0], [if wide { WIDE_LARGE_WIDTH } else { 800.0 }, 660.0]))
+        } else {
+            let (entry, max_len) = if wide { (934.0, 4) } else { (720.0, 3) };
+            (Queue::new(vec![[330.0, 64.0], [entry, 64.0]],
+                        vec![[330.0, -150.0]],
+                        max_len,
+                        Arrivals::regular(),
+                        clock.clone()),
+             Queue::new(vec![[180.0, 64.0], [180.0, -200.0]],
+                        vec![[-100.0, 64.0]],
+                        2,
+                        Arrivals::volunteers(),
+                        clock.clone()),
+             Rectangle::new([0.0, 0.0], [if wide { WIDE_WIDTH } else { 640.0 }, 480.0]))
+        };
+        let volunteers = Rc::new(RefCell::new(volunteers.with_spacing(VOLUNTEER_SPACING)));
+        let regular = Rc::new(RefCell::new(regular));
+        entities.push(volunteers.clone());
+        entities.push(regular.clone());
+        let [left, top, width, _] = view.as_floats();
+        let queues = vec![volunteers, regular.clone()];
+        entities.push(Rc::new(RefCell::new(TicketRail::new([left, top], width - HUD_WIDTH, queues.clone()))));
+
+        // Hints go last so their pointers show over everything
+        let tutorial = Tutorial::new(chapters, regular, clock.clone(), locale.clone(), CAPTION_POS);
+        let tutorial = fixtures.into_iter().fold(tutorial, |tutorial, (name, entity)| tutorial.with_fixture(name, entity));
+        let mut tutorial = sources.fold(tutorial, |tutorial, (item, entity)| tutorial.with_source(item, entity));
+        tutorial.set_keys(keys);
+        let tutorial = Rc::new(RefCell::new(tutorial));
+        entities.push(tutorial.clone());
+        entities.push(Rc::new(RefCell::new(Hints::new(clock.clone(), locale))));
+        let mut scene = Scene{entities, view, clock, tutorial, queues, served: vec![]};
+        scene.set_palette(palette);
+        scene
+    }
+
+    /// The part of the world shown in the window
+    pub fn view(&self) -> Rectangle {
+        self.view
+    }
+
+    pub fn clock(&self) -> Rc<RefCell<Clock>> {
+        self.clock.clone()
+    }
+
+    pub fn tutorial_finished(&self) -> bool {
+        self.tutorial.borrow().finished()
+    }
+
+    pub fn tutorial_chapter(&self) -> usize {
+        self.tutorial.borrow().chapter()
+    }
+
+    pub fn skip_tutorial(&mut self) {
+        self.tutorial.borrow_mut().skip();
+    }
+
+    pub fn set_palette(&mut self, palette: Palette) {
+        for queue in &self.queues {
+            queue.borrow_mut().set_palette(palette);
+        }
+    }
+
+    /// Name newly bound keys in the tutorial's captions
+    pub fn set_keys(&mut self, keys: Keys) {
+        self.tutorial.borrow_mut().set_keys(keys);
+    }
+
+    /// How each bread handed over since the last call went down
+    pub fn take_served(&mut self) -> Vec<Mood> {
+        std::mem::take(&mut self.served)
+    }
+
+    pub fn draw(&self, context: Context, font: &mut Font, graphics: &mut G) {
+        for e in self.entities.iter() {
+            let e = e.borrow();
+            e.draw(context, graphics);
+            e.draw_text(context, font, graphics);
         }
     }
 
     pub fn update(&mut self, dt: f64) {
         let mut new = vec![];
-        for e in self.0.iter() {
+        for e in self.entities.iter() {
             new.append(&mut e.borrow_mut().update(dt));
         }
-        self.0.retain(|e| !e.borrow().expired());
-        self.0.append(&mut new);
+        self.entities.retain(|e| !e.borrow().expired());
+        self.entities.append(&mut new);
     }
 
     pub fn select(&mut self, pos: [f64; 2]) -> Option<Rc<RefCell<dyn Entity>>> {
@@ -46,7 +184,7 @@ impl Scene {
             Append(Rc<RefCell<dyn Entity>>),
         }
 
-        match self.0.iter().rev().find_map(|e| {
+        match self.entities.iter().rev().find_map(|e| {
             match e.borrow_mut().select(pos) {
                 Selection::None => None,
                 Selection::This => Some(Action::Return(e.clone())),
@@ -55,7 +193,7 @@ impl Scene {
         }) {
             Some(Action::Return(e)) => Some(e),
             Some(Action::Append(e)) => {
-                self.0.push(e.clone());
+                self.entities.push(e.clone());
                 Some(e)
             },
             None => None,
@@ -63,22 +201,36 @@ impl Scene {
     }
 
     pub fn grabbed(&mut self, entity: &Rc<RefCell<dyn Entity>>) {
-        let n = self.0.iter().enumerate().filter(|(_, e)| Rc::ptr_eq(e, entity)).next().unwrap().0;
-        let e = self.0.remove(n);
-        self.0.push(e);
+        let n = self.entities.iter().enumerate().find(|(_, e)| Rc::ptr_eq(e, entity)).unwrap().0;
+        let e = self.entities.remove(n);
+        self.entities.push(e);
+    }
+
+    /// Work whatever the held entity has just done as a tool onto the topmost thing under it
+    pub fn tool_used(&mut self, entity: &Rc<RefCell<dyn Entity>>) {
+        let tool_use = entity.borrow_mut().take_tool_use();
+        if let Some((tool, points)) = tool_use {
+            for pos in points {
+                for e in self.entities.iter().rev().filter(|e| !Rc::ptr_eq(e, entity)) {
+                    if e.borrow_mut().use_tool(tool, pos) {
+                        break;
+                    }
+                }
+            }
+        }
     }
 
     pub fn dropped(&mut self, entity: &Rc<RefCell<dyn Entity>>) {
-        for e in self.0.iter().filter(|e| !Rc::ptr_eq(e, entity)) {
+        for e in self.entities.iter().filter(|e| !Rc::ptr_eq(e, entity)) {
             e.borrow_mut().other_dropped(entity);
         }
-        if !entity.borrow().topping().is_none() {
-            for e in self.0.iter().rev().filter(|e| !Rc::ptr_eq(e, entity) && e.borrow().bounds().intersect_rect(&entity.borrow().bounds())) {
+        if entity.borrow().topping().is_some() {
+            for e in self.entities.iter().rev().filter(|e| !Rc::ptr_eq(e, entity) && e.borrow().footprint().intersect(&entity.borrow().footprint())) {
                 let res = e.borrow_mut().add_topping(entity);
                 match res {
                     Selection::This => {
-                        let n = self.0.iter().enumerate().filter(|(_, e)| Rc::ptr_eq(e, entity)).next().unwrap().0;
-                        self.0.remove(n);
+                        let n = self.entities.iter().enumerate().find(|(_, e)| Rc::ptr_eq(e, entity)).unwrap().0;
+                        self.entities.remove(n);
                         return;
                     },
                     Selection::New(_) => {
@@ -90,21 +242,80 @@ impl Scene {
         }
 
         if let Some(order) = entity.borrow().order() {
-            let mut mood = None;
-            for e in self.0.iter().rev().filter(|e| !Rc::ptr_eq(e, entity) && e.borrow().bounds().intersect_rect(&entity.borrow().bounds())) {
-                mood = e.borrow_mut().deliver_order(order);
-                if mood.is_some() {
-                    break;
-                }
-            }
+            // Hand the order to whichever customer it was dropped most squarely on
+            let customer = self.entities.iter()
+                .filter(|e| !Rc::ptr_eq(e, entity))
+                .map(|e| (e, e.borrow().order_overlap(order)))
+                .filter(|(_, overlap)| *overlap > 0.0)
+                .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+                .map(|(e, _)| e.clone());
+            let mood = customer.and_then(|e| e.borrow_mut().deliver_order(order));
             if let Some(mood) = mood {
-                let n = self.0.iter().enumerate().filter(|(_, e)| Rc::ptr_eq(e, entity)).next().unwrap().0;
-                self.0.remove(n);
+                self.served.push(mood);
+                let n = self.entities.iter().enumerate().find(|(_, e)| Rc::ptr_eq(e, entity)).unwrap().0;
+                self.entities.remove(n);
                 return;
             }
         }
 
-        let pos = entity.borrow().bounds().centre();
-        entity.borrow_mut().set_heat(self.0.iter().filter(|e| !Rc::ptr_eq(e, entity)).map(|e| e.borrow().heat(pos)).sum());
+        self.settle(entity);
+
+        // Average the heat over the whole footprint, so anything hanging off the edge cooks slower
+        let points = entity.borrow().footprint().sample_points(HEAT_SAMPLES);
+        let heat = points.iter()
+            .map(|&pos| self.entities.iter().filter(|e| !Rc::ptr_eq(e, entity)).map(|e| e.borrow().heat(pos)).sum::<f64>())
+            .sum::<f64>() / points.len() as f64;
+        entity.borrow_mut().set_heat(heat);
+    }
+
+    /// Find room for something put down on a surface such as the hotplate, so it doesn't sit on top of anything else
+    fn settle(&self, entity: &Rc<RefCell<dyn Entity>>) {
+        if !entity.borrow().solid() {
+            return;
+        }
+        let centre = entity.borrow().bounds().centre();
+        let placements = match self.entities.iter().rev()
+            .filter(|e| !Rc::ptr_eq(e, entity))
+            .find_map(|e| e.borrow().placements(centre)) {
+            Some(placements) => placements,
+            None => return,
+        };
+        let others: Vec<_> = self.entities.iter()
+            .filter(|e| !Rc::ptr_eq(e, entity) && e.borrow().solid())
+            .collect();
+        let overlapping = |entity: &Rc<RefCell<dyn Entity>>| {
+            let footprint = entity.borrow().footprint();
+            others.iter().any(|e| footprint.intersect(&e.borrow().footprint()))
+        };
+
+        // Prefer a free spot, otherwise take the closest and push it clear of its neighbours
+        let mut current = centre;
+        let mut free = false;
+        for pos in &placements {
+            entity.borrow_mut().drag(current, *pos);
+            current = *pos;
+            if !overlapping(entity) {
+                free = true;
+                break;
+            }
+        }
+        if !free {
+            if let Some(pos) = placements.first() {
+                entity.borrow_mut().drag(current, *pos);
+            }
+            for _ in 0..SETTLE_ITERATIONS {
+                let mut moved = false;
+                for e in &others {
+                    let separation = entity.borrow().footprint().separation(&e.borrow().footprint());
+                    if let Some(push) = separation {
+                        entity.borrow_mut().drag([0.0, 0.0], push);
+                        moved = true;
+                    }
+                }
+                if !moved {
+                    break;
+                }
+            }
+        }
     }
 }
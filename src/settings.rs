@@ -0,0 +1,248 @@
+use piston_window::Key;
+use serde::{Deserialize, Serialize};
+
+use crate::colour::Palette;
+use crate::locale::Locale;
+use crate::storage;
+
+const FILE: &str = "settings.toml";
+const WINDOW_SIZES: [[u32; 2]; 6] = [[640, 480], [800, 600], [1024, 768], [1280, 720], [1280, 960], [1920, 1080]];
+const FRAME_CAPS: [u64; 5] = [30, 60, 120, 144, 240];
+
+/// Something the player does with a key, which they can rebind
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Squeeze,
+    RotateLeft,
+    RotateRight,
+    SkipTutorial,
+    Menu,
+    Fullscreen,
+}
+
+impl Action {
+    pub const ALL: [Action; 6] = [Action::Squeeze, Action::RotateLeft, Action::RotateRight, Action::SkipTutorial, Action::Menu, Action::Fullscreen];
+
+    /// Message id of what the action does
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Squeeze => "key_squeeze",
+            Action::RotateLeft => "key_rotate_left",
+            Action::RotateRight => "key_rotate_right",
+            Action::SkipTutorial => "key_skip_tutorial",
+            Action::Menu => "key_menu",
+            Action::Fullscreen => "key_fullscreen",
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Keys {
+    pub squeeze: Key,
+    pub rotate_left: Key,
+    pub rotate_right: Key,
+    pub skip_tutorial: Key,
+    pub menu: Key,
+    pub fullscreen: Key,
+}
+
+impl Default for Keys {
+    fn default() -> Keys {
+        Keys{
+            squeeze: Key::Space,
+            rotate_left: Key::Q,
+            rotate_right: Key::E,
+            skip_tutorial: Key::Tab,
+            menu: Key::Escape,
+            fullscreen: Key::F11,
+        }
+    }
+}
+
+impl Keys {
+    pub fn get(&self, action: Action) -> Key {
+        match action {
+            Action::Squeeze => self.squeeze,
+            Action::RotateLeft => self.rotate_left,
+            Action::RotateRight => self.rotate_right,
+            Action::SkipTutorial => self.skip_tutorial,
+            Action::Menu => self.menu,
+            Action::Fullscreen => self.fullscreen,
+        }
+    }
+
+    /// Bind `key` to `action`, swapping with whatever action had it before so every action keeps a key
+    pub fn set(&mut self, action: Action, key: Key) {
+        let old = self.get(action);
+        if let Some(other) = Action::ALL.iter().find(|a| self.get(**a) == key) {
+            *self.key_mut(*other) = old;
+        }
+        *self.key_mut(action) = key;
+    }
+
+    fn key_mut(&mut self, action: Action) -> &mut Key {
+        match action {
+            Action::Squeeze => &mut self.squeeze,
+            Action::RotateLeft => &mut self.rotate_left,
+            Action::RotateRight => &mut self.rotate_right,
+            Action::SkipTutorial => &mut self.skip_tutorial,
+            Action::Menu => &mut self.menu,
+            Action::Fullscreen => &mut self.fullscreen,
+        }
+    }
+
+    /// What to call `key` on screen, from the locale where it has a name of its own
+    pub fn name(key: Key, locale: &Locale) -> String {
+        let name = format!("{:?}", key);
+        locale.lookup(&format!("key_name_{}", name)).map_or(name, |n| n.to_string())
+    }
+}
+
+/// One of the options on the settings screen
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    WindowSize,
+    Fullscreen,
+    Widescreen,
+    Vsync,
+    MaxFps,
+    Palette,
+}
+
+impl Field {
+    pub const ALL: [Field; 6] = [Field::WindowSize, Field::Fullscreen, Field::Widescreen, Field::Vsync, Field::MaxFps, Field::Palette];
+
+    /// Message id of the option's name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Field::WindowSize => "setting_window_size",
+            Field::Fullscreen => "setting_fullscreen",
+            Field::Widescreen => "setting_widescreen",
+            Field::Vsync => "setting_vsync",
+            Field::MaxFps => "setting_max_fps",
+            Field::Palette => "setting_palette",
+        }
+    }
+
+    /// Whether a change only takes effect the next time the game starts
+    pub fn needs_restart(&self) -> bool {
+        matches!(self, Field::Widescreen | Field::Vsync)
+    }
+}
+
+/// Display and input options, kept between runs
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Settings {
+    /// The window's size in pixels, or none to fit the stall
+    pub window_size: Option<[u32; 2]>,
+    pub fullscreen: bool,
+    /// Lay the stall out to fill a 16:9 screen
+    pub widescreen: bool,
+    pub vsync: bool,
+    pub max_fps: u64,
+    pub palette: Palette,
+    pub keys: Keys,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings{
+            window_size: None,
+            fullscreen: false,
+            widescreen: false,
+            vsync: false,
+            max_fps: 60,
+            palette: Palette::Standard,
+            keys: Keys::default(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn load() -> Settings {
+        storage::load(FILE)
+    }
+
+    pub fn save(&self) {
+        storage::save(FILE, self);
+    }
+
+    /// Step `field` on to its next value, or back to its previous one
+    pub fn change(&mut self, field: Field, forward: bool) {
+        match field {
+            Field::WindowSize => {
+                let sizes: Vec<_> = std::iter::once(None).chain(WINDOW_SIZES.iter().copied().map(Some)).collect();
+                self.window_size = cycle(&sizes, &self.window_size, forward);
+            },
+            Field::Fullscreen => self.fullscreen = !self.fullscreen,
+            Field::Widescreen => self.widescreen = !self.widescreen,
+            Field::Vsync => self.vsync = !self.vsync,
+            Field::MaxFps => self.max_fps = cycle(&FRAME_CAPS, &self.max_fps, forward),
+            Field::Palette => self.palette = cycle(&Palette::ALL, &self.palette, forward),
+        }
+    }
+
+    /// How `field` is currently set, for the settings screen
+    pub fn value(&self, field: Field, locale: &Locale) -> String {
+        let on_off = |on| locale.get(if on { "setting_on" } else { "setting_off" }).to_string();
+        match field {
+            Field::WindowSize => match self.window_size {
+                Some([w, h]) => format!("{} × {}", w, h),
+                None => locale.get("setting_fit").to_string(),
+            },
+            Field::Fullscreen => on_off(self.fullscreen),
+            Field::Widescreen => on_off(self.widescreen),
+            Field::Vsync => on_off(self.vsync),
+            Field::MaxFps => locale.format("fps", &self.max_fps.to_string()),
+            Field::Palette => locale.get(self.palette.name()).to_string(),
+        }
+    }
+}
+
+/// The value after `current` in `values`, or before it going backwards, wrapping around at the ends
+fn cycle<T: Copy + PartialEq>(values: &[T], current: &T, forward: bool) -> T {
+    let i = values.iter().position(|v| v == current).unwrap_or(0);
+    let n = values.len();
+    values[if forward { (i + 1) % n } else { (i + n - 1) % n }]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut settings = Settings::default();
+        settings.change(Field::WindowSize, true);
+        settings.change(Field::Palette, true);
+        settings.keys.set(Action::Squeeze, Key::S);
+        let data = toml::to_string(&settings).unwrap();
+        let loaded: Settings = toml::from_str(&data).unwrap();
+        assert_eq!(loaded.window_size, Some([640, 480]));
+        assert!(loaded.palette == Palette::Colourblind);
+        assert_eq!(loaded.keys.squeeze, Key::S);
+        let defaults: Settings = toml::from_str(&toml::to_string(&Settings::default()).unwrap()).unwrap();
+        assert_eq!(defaults.window_size, None);
+    }
+
+    #[test]
+    fn rebinding_swaps() {
+        let mut keys = Keys::default();
+        keys.set(Action::RotateLeft, Key::E);
+        assert_eq!(keys.rotate_left, Key::E);
+        assert_eq!(keys.rotate_right, Key::Q);
+    }
+
+    #[test]
+    fn key_names() {
+        let en = Locale::load("en").unwrap();
+        let fr = Locale::load("fr").unwrap();
+        assert_eq!(Keys::name(Key::Space, &en), "Space");
+        assert_eq!(Keys::name(Key::Space, &fr), "Espace");
+        assert_eq!(Keys::name(Key::D1, &en), "1");
+        assert_eq!(Keys::name(Key::Q, &fr), "Q");
+        assert_eq!(Keys::name(Key::F11, &en), "F11");
+    }
+}
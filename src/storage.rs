@@ -0,0 +1,43 @@
+use serde::{de::DeserializeOwned, Serialize};
+
+use std::path::PathBuf;
+
+const DIRECTORY: &str = "sizzle";
+
+/// Where the game keeps `file` between runs, in the platform's usual place for settings
+fn path(file: &str) -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME").map(PathBuf::from)
+        .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join(DIRECTORY).join(file))
+}
+
+/// Read `file`, or the defaults if it hasn't been saved yet or can't be read
+pub fn load<T: DeserializeOwned + Default>(file: &str) -> T {
+    path(file)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|data| match toml::from_str(&data) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                eprintln!("Ignoring {}: {}", file, e);
+                None
+            },
+        })
+        .unwrap_or_default()
+}
+
+/// Write `value` to `file`, reporting rather than failing if it can't be saved
+pub fn save<T: Serialize>(file: &str, value: &T) {
+    let result = path(file)
+        .ok_or_else(|| "no settings directory".to_string())
+        .and_then(|path| {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            let data = toml::to_string(value).map_err(|e| e.to_string())?;
+            std::fs::write(path, data).map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        eprintln!("Couldn't save {}: {}", file, e);
+    }
+}
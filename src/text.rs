@@ -0,0 +1,76 @@
+use piston_window::{context::Context, Glyphs, GfxEncoder, PistonWindow, TextureSettings, Transformed};
+use piston_window::character::CharacterCache;
+
+use crate::entity::G;
+
+const FONT: &[u8] = include_bytes!("../assets/fonts/DejaVuSans.ttf");
+const CAPTION_PADDING: f64 = 6.0;
+const CAPTION_BACKGROUND: [f32; 4] = [0.0, 0.0, 0.0, 0.6];
+const WHITE: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+#[derive(Clone, Copy)]
+pub enum Align {
+    Left,
+    Centre,
+    Right,
+}
+
+/// The bundled font, rendered at the window's resolution so text stays sharp however the game is scaled
+pub struct Font {
+    glyphs: Glyphs,
+    scale: f64,
+}
+
+impl Font {
+    pub fn load(window: &mut PistonWindow) -> Font {
+        let glyphs = Glyphs::from_bytes(FONT, window.create_texture_context(), TextureSettings::new())
+            .expect("Invalid font");
+        Font{glyphs, scale: 1.0}
+    }
+
+    /// Set how many pixels there are to a unit of the virtual screen
+    pub fn set_scale(&mut self, scale: f64) {
+        self.scale = scale;
+    }
+
+    fn pixel_size(&self, size: f64) -> u32 {
+        (size * self.scale).round().max(1.0) as u32
+    }
+
+    /// Width of `text` in virtual screen units
+    pub fn width(&mut self, text: &str, size: f64) -> f64 {
+        let pixel_size = self.pixel_size(size);
+        self.glyphs.width(pixel_size, text).unwrap_or(0.0) / self.scale
+    }
+
+    /// Draw a line of text with its baseline at `pos`
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw(&mut self, text: &str, pos: [f64; 2], size: f64, colour: [f32; 4], align: Align, context: Context, graphics: &mut G) {
+        let x = match align {
+            Align::Left => pos[0],
+            Align::Centre => pos[0] - self.width(text, size) / 2.0,
+            Align::Right => pos[0] - self.width(text, size),
+        };
+        let transform = context.transform.trans(x, pos[1]).scale(1.0 / self.scale, 1.0 / self.scale);
+        let pixel_size = self.pixel_size(size);
+        piston_window::text(colour, pixel_size, text, &mut self.glyphs, transform, graphics).ok();
+    }
+
+    /// Draw white text on a dark box centred on `pos`, to stand out over the stall
+    pub fn caption(&mut self, text: &str, pos: [f64; 2], size: f64, context: Context, graphics: &mut G) {
+        let width = self.width(text, size);
+        piston_window::rectangle(CAPTION_BACKGROUND,
+                                 [pos[0] - width / 2.0 - CAPTION_PADDING,
+                                  pos[1] - size / 2.0 - CAPTION_PADDING,
+                                  width + 2.0 * CAPTION_PADDING,
+                                  size + 2.0 * CAPTION_PADDING],
+                                 context.transform,
+                                 graphics);
+        self.draw(text, [pos[0], pos[1] + size * 0.35], size, WHITE, Align::Centre, context, graphics);
+    }
+
+    /// Glyphs are uploaded through their own encoder, which has to be flushed after drawing each frame
+    pub fn encoder(&mut self) -> &mut GfxEncoder {
+        &mut self.glyphs.factory.encoder
+    }
+}
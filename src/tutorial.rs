@@ -0,0 +1,674 @@
+use piston_window::context::Context;
+use serde::Deserialize;
+
+use crate::entity::{G, Entity, Clock, Condiment, Filling, Gesture, OrderSpec, Pointer, Queue, Topping};
+use crate::geometry::Rectangle;
+use crate::locale::Locale;
+use crate::settings::Keys;
+use crate::text::{Align, Font};
+
+use std::cell::RefCell;
+use std::ops::Range;
+use std::rc::Rc;
+
+const SCRIPT: &str = include_str!("../assets/tutorial.toml");
+const CAPTION_SIZE: f64 = 14.0;
+const SKIP_SIZE: f64 = 10.0;
+const SKIP_OFFSET: f64 = 22.0;
+const WHITE: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+/// Anything the player handles which the script can refer to
+#[derive(Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(untagged)]
+pub enum Item {
+    Filling(Filling),
+    Condiment(Condiment),
+    Staple(Staple),
+}
+
+#[derive(Deserialize, PartialEq, Eq, Clone, Copy)]
+pub enum Staple {
+    Bread,
+    Onion,
+}
+
+impl Item {
+    fn of(entity: &dyn Entity) -> Option<Item> {
+        if entity.order().is_some() {
+            return Some(Item::Staple(Staple::Bread));
+        }
+        match entity.topping()? {
+            Topping::Filling(filling) => Some(Item::Filling(filling)),
+            Topping::Condiment(condiment) => Some(Item::Condiment(condiment)),
+            Topping::Onion => Some(Item::Staple(Staple::Onion)),
+        }
+    }
+
+    fn topping(&self) -> Option<Topping> {
+        match self {
+            Item::Filling(filling) => Some(Topping::Filling(*filling)),
+            Item::Condiment(condiment) => Some(Topping::Condiment(*condiment)),
+            Item::Staple(Staple::Onion) => Some(Topping::Onion),
+            Item::Staple(Staple::Bread) => None,
+        }
+    }
+}
+
+/// Somewhere on screen for a pointer to start or end
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Anchor {
+    Point([f64; 2]),
+    /// The last one of these put down
+    Item {
+        item: Item,
+        #[serde(default)]
+        offset: [f64; 2],
+    },
+    /// Where these come from
+    Source {
+        source: Item,
+        #[serde(default)]
+        offset: [f64; 2],
+    },
+    Fixture {
+        fixture: String,
+        #[serde(default)]
+        offset: [f64; 2],
+    },
+}
+
+/// What moves a step on
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Trigger {
+    Dropped {
+        item: Item,
+        #[serde(default)]
+        on: Option<String>,
+        #[serde(default = "one")]
+        count: usize,
+    },
+    /// There are enough of something put down this chapter lying on a fixture, however long ago
+    On {
+        item: Item,
+        fixture: String,
+        #[serde(default = "one")]
+        count: usize,
+    },
+    Cooked {
+        item: Item,
+        side: usize,
+        above: f64,
+        #[serde(default = "one")]
+        count: usize,
+    },
+    Contains {
+        item: Item,
+        #[serde(default = "one")]
+        count: usize,
+    },
+    /// A fixture has something ready to take, like chopped onions
+    Ready(String),
+    Served,
+}
+
+fn one() -> usize {
+    1
+}
+
+/// What the pointer shows
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Motion {
+    Move {
+        from: Anchor,
+        to: Anchor,
+        /// Pulse where the pointer ends up
+        #[serde(default)]
+        pulse: bool,
+    },
+    Gesture {
+        gesture: Gesture,
+        at: Anchor,
+    },
+    Pulse {
+        target: Anchor,
+    },
+}
+
+#[derive(Deserialize)]
+struct Hint {
+    #[serde(flatten)]
+    motion: Motion,
+    /// Message id of a short label by the pointer
+    #[serde(default)]
+    label: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Step {
+    trigger: Trigger,
+    #[serde(default)]
+    pointer: Option<Hint>,
+    #[serde(default)]
+    caption: Option<String>,
+    /// Also check this step's trigger while on earlier steps
+    #[serde(default)]
+    shortcut: bool,
+}
+
+#[derive(Deserialize)]
+struct CustomerSpec {
+    orders: Vec<OrderSpec>,
+}
+
+#[derive(Deserialize)]
+struct Chapter {
+    /// Message id of the name shown in the menu
+    title: String,
+    #[serde(default)]
+    customers: Vec<CustomerSpec>,
+    steps: Vec<Step>,
+}
+
+#[derive(Deserialize)]
+struct Script {
+    chapters: Vec<Chapter>,
+}
+
+/// Plays through the tutorial script, watching what the player does and pointing out what to do next,
+/// then opens the polls once it's over
+pub struct Tutorial {
+    chapters: Vec<Chapter>,
+    chapter: usize,
+    /// The chapter after the last one to play
+    end: usize,
+    step: usize,
+    started: bool,
+    finished: bool,
+    queue: Rc<RefCell<Queue>>,
+    clock: Rc<RefCell<Clock>>,
+    locale: Rc<Locale>,
+    keys: Keys,
+    caption_pos: [f64; 2],
+    sources: Vec<(Item, Rc<RefCell<dyn Entity>>)>,
+    fixtures: Vec<(String, Rc<RefCell<dyn Entity>>)>,
+    /// Everything put down this chapter, most recent last
+    tracked: Vec<(Item, Rc<RefCell<dyn Entity>>)>,
+    /// Everything put down since the step began
+    dropped: Vec<Rc<RefCell<dyn Entity>>>,
+    /// How many breads the queue had been handed when the chapter began
+    served: usize,
+    pointer: Option<Pointer>,
+}
+
+impl Tutorial {
+    /// Play through `chapters` of the tutorial, sending customers to `queue`, with captions centred on `caption_pos`
+    pub fn new(chapters: Range<usize>, queue: Rc<RefCell<Queue>>, clock: Rc<RefCell<Clock>>, locale: Rc<Locale>, caption_pos: [f64; 2]) -> Tutorial {
+        let script = Tutorial::script();
+        Tutorial{
+            end: chapters.end.min(script.chapters.len()),
+            chapter: chapters.start,
+            chapters: script.chapters,
+            step: 0,
+            started: false,
+            finished: false,
+            queue,
+            clock,
+            locale,
+            keys: Keys::default(),
+            caption_pos,
+            sources: vec![],
+            fixtures: vec![],
+            tracked: vec![],
+            dropped: vec![],
+            served: 0,
+            pointer: None,
+        }
+    }
+
+    fn script() -> Script {
+        toml::from_str(SCRIPT).expect("Invalid tutorial script")
+    }
+
+    /// Name the player's own keys in captions
+    pub fn set_keys(&mut self, keys: Keys) {
+        self.keys = keys;
+    }
+
+    /// Look up a caption or label, filling in the squeeze key
+    fn text(&self, id: &str) -> String {
+        self.locale.format(id, &Keys::name(self.keys.squeeze, &self.locale))
+    }
+
+    /// Message ids of the title of each chapter
+    pub fn titles() -> Vec<String> {
+        Tutorial::script().chapters.into_iter().map(|c| c.title).collect()
+    }
+
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    /// The chapter being played, which is also how many come before it
+    pub fn chapter(&self) -> usize {
+        self.chapter
+    }
+
+    /// Go straight to opening the polls
+    pub fn skip(&mut self) {
+        if !self.finished {
+            self.pointer = None;
+            self.finish();
+        }
+    }
+
+    /// Let the script point at where `item` comes from
+    pub fn with_source(mut self, item: Item, entity: Rc<RefCell<dyn Entity>>) -> Tutorial {
+        self.sources.push((item, entity));
+        self
+    }
+
+    /// Let the script refer to `entity` by `name`
+    pub fn with_fixture(mut self, name: &str, entity: Rc<RefCell<dyn Entity>>) -> Tutorial {
+        self.fixtures.push((name.to_string(), entity));
+        self
+    }
+
+    /// The last of `item` put down
+    fn tracked(&self, item: Item) -> Option<&Rc<RefCell<dyn Entity>>> {
+        self.tracked.iter().rfind(|(i, _)| *i == item).map(|(_, e)| e)
+    }
+
+    fn fixture(&self, name: &str) -> Option<&Rc<RefCell<dyn Entity>>> {
+        self.fixtures.iter().find(|(n, _)| n == name).map(|(_, e)| e)
+    }
+
+    fn resolve(&self, anchor: &Anchor) -> Option<[f64; 2]> {
+        let (pos, offset) = match anchor {
+            Anchor::Point(pos) => (*pos, [0.0, 0.0]),
+            Anchor::Item{item, offset} => (self.tracked(*item)?.borrow().get_pos(), *offset),
+            Anchor::Source{source, offset} => (
+                self.sources.iter().find(|(i, _)| i == source)?.1.borrow().get_pos(),
+                *offset,
+            ),
+            Anchor::Fixture{fixture, offset} if fixture == "head" => (self.queue.borrow().head(), *offset),
+            Anchor::Fixture{fixture, offset} => (self.fixture(fixture)?.borrow().get_pos(), *offset),
+        };
+        Some([pos[0] + offset[0], pos[1] + offset[1]])
+    }
+
+    fn lies_on(&self, entity: &dyn Entity, fixture: &str) -> bool {
+        self.fixture(fixture).is_some_and(|f| f.borrow().footprint().intersect(&entity.footprint()))
+    }
+
+    fn triggered(&self, trigger: &Trigger) -> bool {
+        match trigger {
+            Trigger::Dropped{item, on, count} => self.dropped.iter().filter(|e| {
+                let e = e.borrow();
+                Item::of(&*e) == Some(*item) && on.as_ref().is_none_or(|on| self.lies_on(&*e, on))
+            }).count() >= *count,
+            Trigger::On{item, fixture, count} => self.tracked.iter()
+                .filter(|(i, e)| i == item && self.lies_on(&*e.borrow(), fixture))
+                .count() >= *count,
+            Trigger::Cooked{item, side, above, count} => self.tracked.iter()
+                .filter(|(i, e)| i == item && e.borrow().cooked()[*side] > *above)
+                .count() >= *count,
+            Trigger::Contains{item, count} => item.topping().is_some_and(|topping| {
+                self.tracked(Item::Staple(Staple::Bread))
+                    .is_some_and(|bread| bread.borrow().order().is_some_and(|order| order.count(topping) >= *count))
+            }),
+            Trigger::Ready(fixture) => self.fixture(fixture).is_some_and(|f| f.borrow().ready()),
+            Trigger::Served => self.queue.borrow().served() > self.served,
+        }
+    }
+
+    fn begin_step(&mut self, step: usize) {
+        self.step = step;
+        self.dropped.clear();
+        let hint = self.chapters[self.chapter].steps[step].pointer.as_ref();
+        self.pointer = hint.and_then(|hint| self.pointer(hint));
+    }
+
+    fn pointer(&self, hint: &Hint) -> Option<Pointer> {
+        let pointer = match &hint.motion {
+            Motion::Move{from, to, pulse: false} => Pointer::new(self.resolve(from)?, self.resolve(to)?),
+            Motion::Move{from, to, pulse: true} => Pointer::new(self.resolve(from)?, self.resolve(to)?).with_pulse(),
+            Motion::Gesture{gesture, at} => Pointer::gesture(*gesture, self.resolve(at)?),
+            Motion::Pulse{target} => Pointer::pulse(self.resolve(target)?),
+        };
+        Some(match &hint.label {
+            Some(label) => pointer.with_caption(self.text(label)),
+            None => pointer,
+        })
+    }
+
+    fn begin_chapter(&mut self) {
+        let chapter = &self.chapters[self.chapter];
+        let mut queue = self.queue.borrow_mut();
+        for customer in &chapter.customers {
+            queue.arrive(&customer.orders);
+        }
+        self.served = queue.served();
+        drop(queue);
+        self.tracked.clear();
+        self.started = true;
+        self.begin_step(0);
+    }
+
+    /// Move on to the next chapter, opening the polls once there are none left
+    fn end_chapter(&mut self) {
+        self.chapter += 1;
+        self.started = false;
+        self.pointer = None;
+        if self.chapter >= self.end {
+            self.finish();
+        }
+    }
+
+    fn finish(&mut self) {
+        self.finished = true;
+        self.tracked.clear();
+        self.dropped.clear();
+        self.clock.borrow_mut().start();
+        self.queue.borrow_mut().spawn();
+    }
+
+    fn caption(&self) -> Option<String> {
+        if self.finished || !self.started {
+            return None;
+        }
+        let id = self.chapters[self.chapter].steps[self.step].caption.as_ref()?;
+        Some(self.text(id))
+    }
+}
+
+impl Entity for Tutorial {
+    fn bounds(&self) -> Rectangle {
+        Rectangle::centered(self.caption_pos, [0.0, 0.0])
+    }
+
+    fn update(&mut self, dt: f64) -> Vec<Rc<RefCell<dyn Entity>>> {
+        if self.finished {
+            return vec![];
+        }
+        if self.chapter >= self.end {
+            self.finish();
+            return vec![];
+        }
+        if !self.started {
+            self.begin_chapter();
+        }
+
+        let steps = &self.chapters[self.chapter].steps;
+        let next = (self.step + 1..steps.len()).rev()
+            .find(|&i| steps[i].shortcut && self.triggered(&steps[i].trigger))
+            .or_else(|| Some(self.step).filter(|&i| self.triggered(&steps[i].trigger)))
+            .map(|i| i + 1);
+        match next {
+            Some(next) if next >= steps.len() => self.end_chapter(),
+            Some(next) => self.begin_step(next),
+            None => {},
+        }
+
+        if let Some(pointer) = &mut self.pointer {
+            pointer.update(dt);
+        }
+        vec![]
+    }
+
+    fn other_dropped(&mut self, other: &Rc<RefCell<dyn Entity>>) {
+        if self.finished {
+            return;
+        }
+        let item = Item::of(&*other.borrow());
+        if let Some(item) = item {
+            self.tracked.retain(|(_, e)| !Rc::ptr_eq(e, other));
+            self.tracked.push((item, other.clone()));
+            if !self.dropped.iter().any(|e| Rc::ptr_eq(e, other)) {
+                self.dropped.push(other.clone());
+            }
+        }
+    }
+
+    fn draw(&self, context: Context, graphics: &mut G) {
+        if let Some(pointer) = &self.pointer {
+            pointer.draw(context, graphics);
+        }
+    }
+
+    fn draw_text(&self, context: Context, font: &mut Font, graphics: &mut G) {
+        if let Some(pointer) = &self.pointer {
+            pointer.draw_text(context, font, graphics);
+        }
+        if let Some(caption) = self.caption() {
+            font.caption(&caption, self.caption_pos, CAPTION_SIZE, context, graphics);
+            font.draw(&self.locale.format("skip_tutorial", &Keys::name(self.keys.skip_tutorial, &self.locale)),
+                      [self.caption_pos[0], self.caption_pos[1] + SKIP_OFFSET],
+                      SKIP_SIZE,
+                      WHITE,
+                      Align::Centre,
+                      context,
+                      graphics);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrivals::{Arrivals, Day};
+    use crate::entity::{Bread, Cookable};
+
+    #[test]
+    fn script_parses() {
+        assert!(!Tutorial::script().chapters.is_empty());
+    }
+
+    #[test]
+    fn captions_in_every_locale() {
+        let script = Tutorial::script();
+        for code in Locale::codes() {
+            let locale = Locale::load(code).unwrap();
+            let steps = script.chapters.iter().flat_map(|c| &c.steps);
+            let labels = steps.clone().filter_map(|s| s.pointer.as_ref()?.label.as_ref());
+            let titles = script.chapters.iter().map(|c| &c.title);
+            for caption in steps.filter_map(|s| s.caption.as_ref()).chain(labels).chain(titles) {
+                assert_ne!(locale.get(caption), caption, "{} is missing {}", code, caption);
+            }
+        }
+    }
+
+    /// Stands in for anything the script watches, with whatever state a test sets
+    struct Stub {
+        pos: [f64; 2],
+        size: f64,
+        topping: Option<Topping>,
+        cooked: [f64; 2],
+        ready: bool,
+    }
+
+    impl Entity for Stub {
+        fn bounds(&self) -> Rectangle {
+            Rectangle::centered(self.pos, [self.size, self.size])
+        }
+
+        fn topping(&self) -> Option<Topping> {
+            self.topping
+        }
+
+        fn cooked(&self) -> [f64; 2] {
+            self.cooked
+        }
+
+        fn ready(&self) -> bool {
+            self.ready
+        }
+
+        fn draw(&self, _context: Context, _graphics: &mut G) {}
+    }
+
+    fn stub(pos: [f64; 2], size: f64, topping: Option<Topping>) -> Rc<RefCell<Stub>> {
+        Rc::new(RefCell::new(Stub{pos, size, topping, cooked: [0.0, 0.0], ready: false}))
+    }
+
+    fn sausage(pos: [f64; 2]) -> Rc<RefCell<Stub>> {
+        stub(pos, 10.0, Some(Topping::Filling(Filling::Sausage)))
+    }
+
+    fn bread_with_sausage() -> Rc<RefCell<Bread>> {
+        let bread = Rc::new(RefCell::new(Bread::new([0.0, 0.0])));
+        add_sausage(&bread);
+        bread
+    }
+
+    fn add_sausage(bread: &Rc<RefCell<Bread>>) {
+        bread.borrow_mut().add_topping(&(Rc::new(RefCell::new(Cookable::new(Filling::Sausage, [0.0, 0.0]))) as Rc<RefCell<dyn Entity>>));
+    }
+
+    /// A tutorial playing a single chapter of `steps`, with a hotplate and a board at the origin
+    fn tutorial(steps: &str) -> (Tutorial, Rc<RefCell<Queue>>, Rc<RefCell<Stub>>) {
+        let clock = Rc::new(RefCell::new(Clock::new([0.0, 0.0], Day::load())));
+        let queue = Rc::new(RefCell::new(
+            Queue::new(vec![[0.0, 0.0], [100.0, 0.0]], vec![[0.0, 100.0]], 3, Arrivals::regular(), clock.clone())));
+        let board = stub([0.0, 0.0], 100.0, None);
+        let mut tutorial = Tutorial::new(0..1, queue.clone(), clock, Rc::new(Locale::load("en").unwrap()), [0.0, 0.0])
+            .with_fixture("hotplate", stub([0.0, 0.0], 100.0, None))
+            .with_fixture("board", board.clone());
+        let script = format!("[[chapters]]\ntitle = \"chapter_sausage\"\n{}", steps);
+        tutorial.chapters = toml::from_str::<Script>(&script).unwrap().chapters;
+        tutorial.update(0.1);
+        (tutorial, queue, board)
+    }
+
+    fn put_down<E: Entity + 'static>(tutorial: &mut Tutorial, entity: &Rc<RefCell<E>>) {
+        tutorial.other_dropped(&(entity.clone() as Rc<RefCell<dyn Entity>>));
+        tutorial.update(0.1);
+    }
+
+    #[test]
+    fn dropped_on_a_fixture() {
+        let (mut tutorial, _, _) = tutorial(r#"
+            [[chapters.steps]]
+            trigger = { dropped = { item = "Sausage", on = "hotplate", count = 2 } }
+            [[chapters.steps]]
+            trigger = "served"
+        "#);
+        put_down(&mut tutorial, &sausage([300.0, 0.0]));
+        put_down(&mut tutorial, &sausage([10.0, 0.0]));
+        assert_eq!(tutorial.step, 0);
+        put_down(&mut tutorial, &sausage([-10.0, 0.0]));
+        assert_eq!(tutorial.step, 1);
+    }
+
+    #[test]
+    fn on_counts_what_was_put_down_before_the_step() {
+        let (mut tutorial, _, _) = tutorial(r#"
+            [[chapters.steps]]
+            trigger = { dropped = { item = "Bread" } }
+            [[chapters.steps]]
+            trigger = { on = { item = "Sausage", fixture = "hotplate", count = 2 } }
+            [[chapters.steps]]
+            trigger = "served"
+        "#);
+        let sausages = [sausage([10.0, 0.0]), sausage([-10.0, 0.0])];
+        for s in &sausages {
+            put_down(&mut tutorial, s);
+        }
+        put_down(&mut tutorial, &bread_with_sausage());
+        tutorial.update(0.1);
+        assert_eq!(tutorial.step, 2);
+    }
+
+    #[test]
+    fn on_only_counts_what_is_still_there() {
+        let (mut tutorial, _, _) = tutorial(r#"
+            [[chapters.steps]]
+            trigger = { on = { item = "Sausage", fixture = "hotplate", count = 2 } }
+            [[chapters.steps]]
+            trigger = "served"
+        "#);
+        let sausages = [sausage([10.0, 0.0]), sausage([-10.0, 0.0])];
+        put_down(&mut tutorial, &sausages[0]);
+        sausages[0].borrow_mut().pos = [300.0, 0.0];
+        put_down(&mut tutorial, &sausages[1]);
+        assert_eq!(tutorial.step, 0);
+        sausages[0].borrow_mut().pos = [0.0, 20.0];
+        tutorial.update(0.1);
+        assert_eq!(tutorial.step, 1);
+    }
+
+    #[test]
+    fn cooked_counts_everything_put_down() {
+        let (mut tutorial, _, _) = tutorial(r#"
+            [[chapters.steps]]
+            trigger = { cooked = { item = "Sausage", side = 0, above = 0.9, count = 2 } }
+            [[chapters.steps]]
+            trigger = "served"
+        "#);
+        let sausages = [sausage([0.0, 0.0]), sausage([20.0, 0.0])];
+        for s in &sausages {
+            put_down(&mut tutorial, s);
+        }
+        sausages[0].borrow_mut().cooked = [1.0, 0.0];
+        tutorial.update(0.1);
+        assert_eq!(tutorial.step, 0);
+        sausages[1].borrow_mut().cooked = [1.0, 0.0];
+        tutorial.update(0.1);
+        assert_eq!(tutorial.step, 1);
+    }
+
+    #[test]
+    fn contains_looks_at_the_last_bread() {
+        let (mut tutorial, _, _) = tutorial(r#"
+            [[chapters.steps]]
+            trigger = { contains = { item = "Sausage", count = 2 } }
+            [[chapters.steps]]
+            trigger = "served"
+        "#);
+        let bread = bread_with_sausage();
+        put_down(&mut tutorial, &bread);
+        assert_eq!(tutorial.step, 0);
+        add_sausage(&bread);
+        tutorial.update(0.1);
+        assert_eq!(tutorial.step, 1);
+    }
+
+    #[test]
+    fn ready_then_served() {
+        let (mut tutorial, queue, board) = tutorial(r#"
+            [[chapters.customers]]
+            orders = [{ fillings = ["Sausage"] }]
+            [[chapters.steps]]
+            trigger = { ready = "board" }
+            [[chapters.steps]]
+            trigger = "served"
+        "#);
+        tutorial.update(0.1);
+        assert_eq!(tutorial.step, 0);
+        board.borrow_mut().ready = true;
+        tutorial.update(0.1);
+        assert_eq!(tutorial.step, 1);
+        assert!(queue.borrow_mut().deliver_order(&Bread::new([100.0, 0.0])).is_some());
+        tutorial.update(0.1);
+        assert!(tutorial.finished());
+    }
+
+    #[test]
+    fn shortcut_skips_ahead() {
+        let (mut tutorial, _, _) = tutorial(r#"
+            [[chapters.steps]]
+            trigger = { dropped = { item = "Bread" } }
+            [[chapters.steps]]
+            trigger = { dropped = { item = "Sausage" } }
+            [[chapters.steps]]
+            trigger = { contains = { item = "Sausage" } }
+            shortcut = true
+            [[chapters.steps]]
+            trigger = "served"
+        "#);
+        put_down(&mut tutorial, &bread_with_sausage());
+        assert_eq!(tutorial.step, 3);
+    }
+}
@@ -0,0 +1,118 @@
+use piston_window::{context::Context, math, Transformed};
+
+use crate::geometry::Rectangle;
+
+/// Fits the scene's view into the window, scaled up as far as it goes and centred, with black bars
+/// covering whatever the view doesn't fill
+#[derive(Clone, Copy)]
+pub struct Viewport {
+    view: Rectangle,
+    /// Window pixels to a unit of the scene
+    scale: f64,
+    /// Window pixels of bar on the left and right, and on the top and bottom
+    letterbox: [f64; 2],
+}
+
+impl Viewport {
+    pub fn new(view: Rectangle, window_size: [f64; 2]) -> Viewport {
+        let [_, _, view_w, view_h] = view.as_floats();
+        let scale = (window_size[0] / view_w).min(window_size[1] / view_h);
+        let letterbox = [
+            ((window_size[0] - view_w * scale) / 2.0).max(0.0),
+            ((window_size[1] - view_h * scale) / 2.0).max(0.0),
+        ];
+        Viewport{view, scale, letterbox}
+    }
+
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    fn scene_transform(&self) -> math::Matrix2d {
+        let [view_x, view_y, _, _] = self.view.as_floats();
+        math::multiply(
+            math::translate([view_x, view_y]),
+            math::multiply(
+                math::scale(1.0 / self.scale, 1.0 / self.scale),
+                math::translate([-self.letterbox[0], -self.letterbox[1]]),
+            ),
+        )
+    }
+
+    /// Where a point in the window is in the scene
+    pub fn to_scene(self, pos: [f64; 2]) -> [f64; 2] {
+        math::transform_pos(self.scene_transform(), pos)
+    }
+
+    /// Set up `context` to draw in scene coordinates
+    pub fn context(&self, context: Context) -> Context {
+        let [view_x, view_y, _, _] = self.view.as_floats();
+        context
+            .trans(self.letterbox[0], self.letterbox[1])
+            .scale(self.scale, self.scale)
+            .trans(-view_x, -view_y)
+    }
+
+    /// The bars either side of the view, in scene coordinates
+    pub fn bars(&self) -> Vec<[f64; 4]> {
+        let [view_x, view_y, view_w, view_h] = self.view.as_floats();
+        let [h, v] = [self.letterbox[0] / self.scale, self.letterbox[1] / self.scale];
+        let mut bars = vec![];
+        if h > 0.0 {
+            bars.push([view_x - h, view_y, h, view_h]);
+            bars.push([view_x + view_w, view_y, h, view_h]);
+        }
+        if v > 0.0 {
+            bars.push([view_x, view_y - v, view_w, v]);
+            bars.push([view_x, view_y + view_h, view_w, v]);
+        }
+        bars
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: [f64; 2], b: [f64; 2]) -> bool {
+        (a[0] - b[0]).abs() < 1e-9 && (a[1] - b[1]).abs() < 1e-9
+    }
+
+    #[test]
+    fn exact_fit() {
+        let viewport = Viewport::new(Rectangle::new([0.0, 0.0], [640.0, 480.0]), [1280.0, 960.0]);
+        assert_eq!(viewport.scale(), 2.0);
+        assert!(viewport.bars().is_empty());
+        assert!(close(viewport.to_scene([640.0, 480.0]), [320.0, 240.0]));
+    }
+
+    #[test]
+    fn pillarboxed_on_a_wide_window() {
+        let viewport = Viewport::new(Rectangle::new([0.0, 0.0], [640.0, 480.0]), [1920.0, 1080.0]);
+        assert_eq!(viewport.scale(), 2.25);
+        // 1920 - 640 * 2.25 leaves 240 pixels either side
+        assert!(close(viewport.to_scene([240.0, 0.0]), [0.0, 0.0]));
+        assert!(close(viewport.to_scene([1680.0, 1080.0]), [640.0, 480.0]));
+        let bars = viewport.bars();
+        assert_eq!(bars.len(), 2);
+        assert!(close([bars[0][0], bars[0][2]], [-240.0 / 2.25, 240.0 / 2.25]));
+    }
+
+    #[test]
+    fn letterboxed_on_a_tall_window() {
+        let viewport = Viewport::new(Rectangle::new([-80.0, -180.0], [800.0, 660.0]), [800.0, 800.0]);
+        assert_eq!(viewport.scale(), 1.0);
+        assert!(close(viewport.to_scene([0.0, 70.0]), [-80.0, -180.0]));
+        assert_eq!(viewport.bars().len(), 2);
+    }
+
+    #[test]
+    fn matches_the_drawing_transform() {
+        let viewport = Viewport::new(Rectangle::new([-80.0, -180.0], [1173.0, 660.0]), [1024.0, 768.0]);
+        let context = viewport.context(Context::new());
+        for pos in [[0.0, 0.0], [12.5, -40.0], [1000.0, 600.0]] {
+            let window = math::transform_pos(context.transform, pos);
+            assert!(close(viewport.to_scene(window), pos));
+        }
+    }
+}